@@ -12,15 +12,18 @@ pub struct WantsToMelee {
 
 #[derive(Component, Clone, ConvertSaveload)]
 pub struct SufferDamage {
-	pub amount: Vec<i32>
+	pub amount: Vec<i32>,
+	pub sources: Vec<String>
 }
 
 impl SufferDamage {
-	pub fn new_damage(store: &mut WriteStorage<SufferDamage>, victim: Entity, amount: i32) {
+	/// `source` describes what dealt the damage, e.g. "a Goblin", and is kept for the death screen.
+	pub fn new_damage<S: ToString>(store: &mut WriteStorage<SufferDamage>, victim: Entity, amount: i32, source: S) {
 		if let Some(suffering) = store.get_mut(victim) {
 			suffering.amount.push(amount);
+			suffering.sources.push(source.to_string());
 		} else {
-			let dmg = SufferDamage { amount: vec![amount] };
+			let dmg = SufferDamage { amount: vec![amount], sources: vec![source.to_string()] };
 			store.insert(victim, dmg).expect("Unable to insert damage");
 		}
 	}
@@ -1,11 +1,10 @@
-use rltk::RGB;
 use serde::{Deserialize, Serialize};
 use specs_derive::{Component, ConvertSaveload};
 use specs::{prelude::*, saveload::Marker};
 use specs::saveload::ConvertSaveload;
 use specs::error::NoError;
 
-use crate::resource::map::Map;
+use crate::resource::{map::Map, palette::Color, run_stats::RunStats};
 
 #[derive(Component, Clone, ConvertSaveload)]
 pub struct Position {
@@ -23,13 +22,13 @@ impl Position {
 #[derive(Component, Clone, ConvertSaveload)]
 pub struct Renderable {
 	pub glyph: rltk::FontCharType,
-	pub fg: RGB,
-	pub bg: RGB,
+	pub fg: Color,
+	pub bg: Color,
 	pub render_order: i32
 }
 
 impl Renderable {
-	pub fn new(glyph: rltk::FontCharType, fg: RGB, bg: RGB, render_order: i32) -> Self {
+	pub fn new(glyph: rltk::FontCharType, fg: Color, bg: Color, render_order: i32) -> Self {
 		Self { glyph, fg, bg, render_order }
 	}
 }
@@ -55,6 +54,18 @@ impl Name {
 	}
 }
 
+/// Flavour text shown when the entity is examined.
+#[derive(Component, Clone, ConvertSaveload)]
+pub struct Description {
+	pub text: String
+}
+
+impl Description {
+	pub fn new<S: ToString>(text: S) -> Self {
+		Self { text: text.to_string() }
+	}
+}
+
 #[derive(Component, Clone, Serialize, Deserialize)]
 #[storage(NullStorage)]
 pub struct BlocksTile {}
@@ -74,6 +85,10 @@ impl CombatStats {
 			defense, power
 		}
 	}
+
+	pub fn carry_capacity(&self) -> f32 {
+		(self.power * 4) as f32
+	}
 }
 
 #[derive(Component, Clone, Serialize, Deserialize)]
@@ -82,5 +97,6 @@ pub struct SerializeMe;
 
 #[derive(Component, Clone, ConvertSaveload)]
 pub struct SerializationHelper {
-	pub map: Map
+	pub map: Map,
+	pub stats: RunStats
 }
@@ -11,3 +11,44 @@ impl Monster {
 		Self { }
 	}
 }
+
+/// Which of the spawner's loot tables a monster rolls on when it dies.
+#[derive(PartialEq, Copy, Clone, Debug, Serialize, Deserialize)]
+pub enum Loot {
+	Kobold,
+	Goblin,
+	Orc,
+	Skeleton,
+	Spider,
+	Troll,
+	Ogre,
+	Boss,
+}
+
+#[derive(Component, Clone, Serialize, Deserialize)]
+pub struct LootTable {
+	pub table: Loot,
+	pub chance: i32,
+}
+
+impl LootTable {
+	pub fn new(table: Loot, chance: i32) -> Self {
+		Self { table, chance }
+	}
+}
+
+#[derive(Component, Clone, Serialize, Deserialize)]
+#[storage(NullStorage)]
+pub struct LeavesCorpse {}
+
+#[derive(Component, Clone, Serialize, Deserialize)]
+pub struct Decays {
+	pub turns: i32,
+}
+
+#[derive(Component, Clone, Serialize, Deserialize)]
+pub struct Boss {
+	pub breath_damage: i32,
+	pub breath_range: i32,
+	pub cooldown: i32,
+}
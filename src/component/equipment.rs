@@ -0,0 +1,32 @@
+use specs_derive::{Component, ConvertSaveload};
+use specs::prelude::*;
+use serde::{Deserialize, Serialize};
+use specs::saveload::*;
+use specs::error::NoError;
+
+#[derive(PartialEq, Copy, Clone, Debug, Serialize, Deserialize)]
+pub enum EquipmentSlot {
+	Melee,
+	Shield,
+}
+
+#[derive(Component, Clone, Serialize, Deserialize)]
+pub struct Equippable {
+	pub slot: EquipmentSlot
+}
+
+#[derive(Component, Clone, ConvertSaveload)]
+pub struct Equipped {
+	pub owner: Entity,
+	pub slot: EquipmentSlot
+}
+
+#[derive(Component, Clone, ConvertSaveload)]
+pub struct MeleePowerBonus {
+	pub power: i32
+}
+
+#[derive(Component, Clone, ConvertSaveload)]
+pub struct DefenseBonus {
+	pub defense: i32
+}
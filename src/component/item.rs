@@ -3,8 +3,12 @@ use specs_derive::{Component, ConvertSaveload};
 use specs::prelude::*;
 use serde::{Deserialize, Serialize};
 use specs::saveload::*;
+use specs::storage::GenericReadStorage;
+use specs::world::EntitiesRes;
 use specs::error::NoError;
 
+use super::Equipped;
+
 #[derive(Component, Clone, Serialize, Deserialize)]
 #[storage(NullStorage)]
 pub struct Item {}
@@ -57,6 +61,12 @@ pub struct WantsToDropItem {
 	pub item: Entity
 }
 
+#[derive(Component, Clone, ConvertSaveload)]
+pub struct WantsToThrowItem {
+	pub item: Entity,
+	pub target: Point
+}
+
 #[derive(Component, Clone, Serialize, Deserialize)]
 pub struct Consumable {}
 
@@ -79,3 +89,61 @@ pub struct AreaOfEffect {
 pub struct Confusion {
 	pub turns: i32
 }
+
+#[derive(Component, Clone, ConvertSaveload)]
+pub struct Charges {
+	pub current: i32,
+	pub max: i32,
+	pub crumbles: bool,
+}
+
+impl Charges {
+	pub fn new(max: i32, crumbles: bool) -> Self {
+		Self { current: max, max, crumbles }
+	}
+}
+
+#[derive(Component, Clone, Serialize, Deserialize)]
+pub struct Recharge {}
+
+#[derive(Component, Clone, ConvertSaveload)]
+pub struct Stackable {
+	pub quantity: i32,
+}
+
+impl Stackable {
+	pub fn new(quantity: i32) -> Self {
+		Self { quantity }
+	}
+}
+
+#[derive(Component, Clone, ConvertSaveload)]
+pub struct Weight {
+	pub weight: f32,
+}
+
+impl Weight {
+	/// Everything `owner` has on them, in the pack or worn.
+	pub fn carried_by<B, E, W, S>(owner: Entity, entities: &EntitiesRes, backpack: &B, equipped: &E, weights: &W, stacks: &S) -> f32
+	where
+		B: GenericReadStorage<Component = InBackpack>,
+		E: GenericReadStorage<Component = Equipped>,
+		W: GenericReadStorage<Component = Weight>,
+		S: GenericReadStorage<Component = Stackable>,
+	{
+		entities.join()
+			.filter(|item| {
+				backpack.get(*item).is_some_and(|pack| pack.owner == owner)
+					|| equipped.get(*item).is_some_and(|worn| worn.owner == owner)
+			})
+			.filter_map(|item| {
+				let quantity = stacks.get(item).map(|s| s.quantity).unwrap_or(1);
+				weights.get(item).map(|w| w.weight * quantity as f32)
+			})
+			.sum()
+	}
+}
+
+#[derive(Component, Clone, Serialize, Deserialize)]
+#[storage(NullStorage)]
+pub struct QuestItem {}
@@ -12,12 +12,16 @@ mod common;
 mod enemy;
 mod player;
 mod combat;
+mod shop;
+mod equipment;
 
 pub use item::*;
 pub use common::*;
 pub use enemy::*;
 pub use player::*;
 pub use combat::*;
+pub use shop::*;
+pub use equipment::*;
 
 #[derive(Component, Clone, ConvertSaveload)]
 pub struct Viewshed {
@@ -53,9 +57,46 @@ pub fn register_components(ecs: &mut World) {
 	ecs.register::<WantsToPickupItem>();
 	ecs.register::<WantsToUseItem>();
 	ecs.register::<WantsToDropItem>();
+	ecs.register::<WantsToThrowItem>();
 	ecs.register::<Consumable>();
 	ecs.register::<Ranged>();
 	ecs.register::<InflictsDamage>();
 	ecs.register::<AreaOfEffect>();
 	ecs.register::<Confusion>();
+	ecs.register::<Charges>();
+	ecs.register::<Recharge>();
+	ecs.register::<Stackable>();
+	ecs.register::<Weight>();
+	ecs.register::<Purse>();
+	ecs.register::<Gold>();
+	ecs.register::<Value>();
+	ecs.register::<Vendor>();
+	ecs.register::<LootTable>();
+	ecs.register::<LeavesCorpse>();
+	ecs.register::<Decays>();
+	ecs.register::<Equippable>();
+	ecs.register::<Equipped>();
+	ecs.register::<MeleePowerBonus>();
+	ecs.register::<DefenseBonus>();
+	ecs.register::<Boss>();
+	ecs.register::<QuestItem>();
+	ecs.register::<Description>();
+}
+
+macro_rules! count_components {
+	($ecs:expr, $($type:ty),*) => {
+		vec![$((stringify!($type), (&$ecs.read_storage::<$type>()).join().count()),)*]
+	};
+}
+
+/// How many entities carry each component, for the wizard console.
+pub fn component_counts(ecs: &World) -> Vec<(&'static str, usize)> {
+	count_components!(ecs,
+		Position, Renderable, Player, Viewshed, Monster, Name, BlocksTile, CombatStats,
+		WantsToMelee, SufferDamage, Item, ProvidesHealing, InBackpack, WantsToPickupItem,
+		WantsToUseItem, WantsToDropItem, WantsToThrowItem, Consumable, Ranged, InflictsDamage, AreaOfEffect,
+		Confusion, Charges, Recharge, Stackable, Weight, Purse, Gold, Value, Vendor,
+		LootTable, LeavesCorpse, Decays, Equippable, Equipped, MeleePowerBonus, DefenseBonus,
+		Boss, QuestItem, Description
+	)
 }
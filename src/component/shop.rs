@@ -0,0 +1,36 @@
+use specs_derive::{Component, ConvertSaveload};
+use specs::prelude::*;
+use serde::{Deserialize, Serialize};
+use specs::saveload::*;
+use specs::error::NoError;
+
+#[derive(Component, Clone, ConvertSaveload)]
+pub struct Purse {
+	pub gold: i32
+}
+
+impl Purse {
+	pub fn new(gold: i32) -> Self {
+		Self { gold }
+	}
+}
+
+#[derive(Component, Clone, ConvertSaveload)]
+pub struct Gold {
+	pub amount: i32
+}
+
+#[derive(Component, Clone, ConvertSaveload)]
+pub struct Value {
+	pub gold: i32
+}
+
+impl Value {
+	pub fn sell_price(&self) -> i32 {
+		i32::max(1, self.gold / 2)
+	}
+}
+
+#[derive(Component, Clone, Serialize, Deserialize)]
+#[storage(NullStorage)]
+pub struct Vendor {}
@@ -6,16 +6,63 @@ mod utils;
 
 mod state;
 
-fn main() -> rltk::BError {
-    let mut context = rltk::RltkBuilder::simple80x50()
+use resource::{settings::Settings, tileset::{self, GraphicsMode}};
+
+rltk::embedded_resource!(TILE_FONT, "../resources/tiles.png");
+
+fn build_context(settings: &Settings) -> rltk::BResult<rltk::Rltk> {
+    let font = settings.font.file();
+    let (glyph_width, glyph_height) = settings.font.glyph_size();
+    let builder = match settings.graphics {
+        GraphicsMode::Ascii => {
+            let (tile_width, tile_height) = settings.tile_dimensions();
+            rltk::RltkBuilder::new()
+                .with_dimensions(80, 50)
+                .with_tile_dimensions(tile_width, tile_height)
+                .with_font(font, glyph_width, glyph_height)
+                .with_simple_console(80, 50, font)
+        }
+        GraphicsMode::Tiles => {
+            rltk::link_resource!(TILE_FONT, "resources/tiles.png");
+            rltk::RltkBuilder::new()
+                .with_dimensions(80, 50)
+                .with_tile_dimensions(settings.tile_size, settings.tile_size)
+                .with_font(tileset::TILE_FONT, 16, 16)
+                .with_font(font, glyph_width, glyph_height)
+                .with_simple_console(80, 50, tileset::TILE_FONT)
+                .with_sparse_console_no_bg(80, 50, tileset::TILE_FONT)
+                .with_sparse_console(80, 50, font)
+        }
+    };
+    builder
         .with_title("Roguelike Tutorial")
-        .with_fullscreen(true)
-        .with_tile_dimensions(16, 16)
-        .build()?;
-    context.with_post_scanlines(true);
-    context.with_mouse_visibility(false);
+        .with_fullscreen(settings.fullscreen)
+        .build()
+}
+
+fn main() -> rltk::BError {
+    let args = std::env::args().collect::<Vec<_>>();
+    let mut settings = Settings::load();
+    if args.iter().any(|arg| arg == "--tiles") {
+        settings.graphics = GraphicsMode::Tiles;
+    }
+
+    let mut context = build_context(&settings)?;
+    context.with_post_scanlines(settings.scanlines);
+    context.with_mouse_visibility(settings.show_mouse);
+    resource::palette::set_palette(settings.palette);
 
     let mut gs = state::State::new();
+    gs.wizard = args.iter().any(|arg| arg == "--wizard");
+    gs.graphics = settings.graphics;
+    gs.settings = settings;
     gs.setup();
+
+    if let Some(path) = args.iter().skip_while(|arg| *arg != "--replay").nth(1) {
+        match system::replay::Replay::load(path) {
+            Some(replay) => gs.start_playback(replay),
+            None => eprintln!("Unable to load replay {}", path),
+        }
+    }
     rltk::main_loop(context, gs)
 }
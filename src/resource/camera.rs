@@ -0,0 +1,50 @@
+use rltk::Point;
+use specs::World;
+
+use super::{map::Map, player::PlayerData};
+
+/// Size of the part of the screen the map is drawn in; the rows below belong to the UI.
+pub const VIEW_WIDTH: i32 = 80;
+pub const VIEW_HEIGHT: i32 = 43;
+
+/// The window of the map that is on screen, following the player but never scrolling past
+/// the map edges. Maps smaller than the view are centred in it.
+#[derive(Clone, Copy)]
+pub struct Camera {
+    pub min_x: i32,
+    pub min_y: i32,
+}
+
+impl Camera {
+    pub fn new(ecs: &World) -> Self {
+        let map = ecs.fetch::<Map>();
+        let player = ecs.fetch::<PlayerData>().position;
+        Self {
+            min_x: Self::axis_start(player.x, map.width, VIEW_WIDTH),
+            min_y: Self::axis_start(player.y, map.height, VIEW_HEIGHT),
+        }
+    }
+
+    fn axis_start(center: i32, map_size: i32, view_size: i32) -> i32 {
+        if map_size <= view_size {
+            return (map_size - view_size) / 2;
+        }
+        (center - view_size / 2).clamp(0, map_size - view_size)
+    }
+
+    /// Screen cell showing a map position, if it is in view.
+    pub fn to_screen(self, world: Point) -> Option<Point> {
+        let screen = Point::new(world.x - self.min_x, world.y - self.min_y);
+        Self::in_view(screen).then_some(screen)
+    }
+
+    /// Map position under a screen cell, if the cell is part of the map view. The position
+    /// may still lie outside the map itself.
+    pub fn to_world(self, screen: Point) -> Option<Point> {
+        Self::in_view(screen).then(|| Point::new(screen.x + self.min_x, screen.y + self.min_y))
+    }
+
+    fn in_view(screen: Point) -> bool {
+        screen.x >= 0 && screen.x < VIEW_WIDTH && screen.y >= 0 && screen.y < VIEW_HEIGHT
+    }
+}
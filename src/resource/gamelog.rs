@@ -1,17 +1,110 @@
+use std::fmt;
+
+use crate::component::Renderable;
+
+use super::palette::Color;
+
+/// A run of text within a log entry, drawn in one colour.
+#[derive(Clone, PartialEq)]
+pub struct LogFragment {
+	pub text: String,
+	pub color: Color,
+}
+
+#[derive(Clone)]
+pub struct LogEntry {
+	/// The turn the message was last logged on.
+	pub turn: i32,
+	pub fragments: Vec<LogFragment>,
+	/// How many times in a row the message came up.
+	pub count: i32,
+}
+
+impl fmt::Display for LogEntry {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		for fragment in self.fragments.iter() {
+			write!(f, "{}", fragment.text)?;
+		}
+		if self.count > 1 {
+			write!(f, " x{}", self.count)?;
+		}
+		Ok(())
+	}
+}
+
 #[derive(Default)]
 pub struct GameLog {
-	pub entries: Vec<String>
+	pub entries: Vec<LogEntry>,
+	/// How many entries are kept; older ones are dropped as new ones arrive.
+	pub limit: usize,
+	/// Turn new entries are stamped with, kept in step with `RunStats::turns`.
+	pub turn: i32,
 }
 
 impl GameLog {
 
-	pub fn new() -> Self {
-		Self {
-			entries: vec!["Hello to DF".to_string()]
-		}
+	pub fn new(limit: usize) -> Self {
+		let mut log = Self { entries: Vec::new(), limit, turn: 0 };
+		log.log("Hello to DF".to_string());
+		log
 	}
 
+	/// Logs a plain message in the default text colour.
 	pub fn log(&mut self, message: String) {
-		self.entries.push(message);
+		self.entry().text(message).log();
 	}
-}
\ No newline at end of file
+
+	/// Starts a message made of coloured fragments; nothing is logged until `log` is called on it.
+	pub fn entry(&mut self) -> LogBuilder<'_> {
+		LogBuilder { log: self, fragments: Vec::new() }
+	}
+
+	fn push(&mut self, fragments: Vec<LogFragment>) {
+		if let Some(last) = self.entries.last_mut() {
+			if last.fragments == fragments {
+				last.count += 1;
+				last.turn = self.turn;
+				return;
+			}
+		}
+		self.entries.push(LogEntry { turn: self.turn, fragments, count: 1 });
+		if self.entries.len() > self.limit {
+			let excess = self.entries.len() - self.limit;
+			self.entries.drain(..excess);
+		}
+	}
+}
+
+pub struct LogBuilder<'a> {
+	log: &'a mut GameLog,
+	fragments: Vec<LogFragment>,
+}
+
+impl LogBuilder<'_> {
+	pub fn colored<S: ToString>(mut self, color: Color, text: S) -> Self {
+		self.fragments.push(LogFragment { text: text.to_string(), color });
+		self
+	}
+
+	pub fn text<S: ToString>(self, text: S) -> Self {
+		self.colored(Color::UiText, text)
+	}
+
+	/// An entity's name, in the colour it is drawn with on the map.
+	pub fn name<S: ToString>(self, name: S, render: Option<&Renderable>) -> Self {
+		let color = render.map(|render| render.fg).unwrap_or(Color::UiText);
+		self.colored(color, name)
+	}
+
+	pub fn damage(self, amount: i32) -> Self {
+		self.colored(Color::Damage, format!("{} hp", amount))
+	}
+
+	pub fn healing(self, amount: i32) -> Self {
+		self.colored(Color::Heal, format!("{} hp", amount))
+	}
+
+	pub fn log(self) {
+		self.log.push(self.fragments);
+	}
+}
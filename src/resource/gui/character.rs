@@ -0,0 +1,127 @@
+use rltk::VirtualKeyCode;
+use specs::{Join, World, WorldExt};
+
+use crate::{
+	component::*,
+	resource::{map::Map, palette::Color, player::PlayerEntity, run_stats::RunStats, wizard::WizardMode},
+};
+
+use super::SummaryResult;
+
+fn heading(ctx: &mut rltk::Rltk, x: i32, y: i32, text: &str) {
+	ctx.print_color(x, y, Color::UiTitle.rgb(), Color::Background.rgb(), text);
+}
+
+fn line(ctx: &mut rltk::Rltk, x: i32, y: i32, color: Color, text: &str) {
+	ctx.print_color(x, y, color.rgb(), Color::Background.rgb(), text);
+}
+
+/// The player's stats, gear, conditions and progress through the run.
+pub fn character_sheet(ecs: &World, ctx: &mut rltk::Rltk) -> SummaryResult {
+	let player = ecs.fetch::<PlayerEntity>().0;
+	let entities = ecs.entities();
+	let names = ecs.read_storage::<Name>();
+	let renderables = ecs.read_storage::<Renderable>();
+	let equipped = ecs.read_storage::<Equipped>();
+	let power_bonus = ecs.read_storage::<MeleePowerBonus>();
+	let defense_bonus = ecs.read_storage::<DefenseBonus>();
+	let combat_stats = ecs.read_storage::<CombatStats>();
+	let Some(stats) = combat_stats.get(player) else {
+		return SummaryResult::Dismissed;
+	};
+
+	ctx.draw_box(10, 3, 59, 39, Color::UiText.rgb(), Color::Background.rgb());
+	ctx.print_color(13, 3, Color::UiHighlight.rgb(), Color::Background.rgb(), "Character");
+
+	let gear = (&entities, &equipped).join()
+		.filter(|(_, worn)| worn.owner == player)
+		.map(|(item, worn)| (item, worn.slot))
+		.collect::<Vec<_>>();
+	let gear_power = gear.iter().filter_map(|(item, _)| power_bonus.get(*item)).map(|b| b.power).sum::<i32>();
+	let gear_defense = gear.iter().filter_map(|(item, _)| defense_bonus.get(*item)).map(|b| b.defense).sum::<i32>();
+
+	let mut y = 5;
+	heading(ctx, 13, y, "Combat");
+	y += 1;
+	let hp_color = if stats.hp * 4 <= stats.max_hp { Color::UiDanger } else { Color::UiText };
+	line(ctx, 15, y, hp_color, &format!("Hit points: {}/{}", stats.hp, stats.max_hp));
+	y += 1;
+	line(ctx, 15, y, Color::UiText, &format!("Power:      {} (base {}, gear +{})", stats.power + gear_power, stats.power, gear_power));
+	y += 1;
+	line(ctx, 15, y, Color::UiText, &format!("Defense:    {} (base {}, gear +{})", stats.defense + gear_defense, stats.defense, gear_defense));
+	y += 2;
+
+	heading(ctx, 13, y, "Equipment");
+	y += 1;
+	if gear.is_empty() {
+		line(ctx, 15, y, Color::UiDim, "Nothing equipped");
+		y += 1;
+	}
+	for (item, slot) in gear.iter() {
+		let color = renderables.get(*item).map(|render| render.fg).unwrap_or(Color::UiText);
+		line(ctx, 15, y, Color::UiDim, &format!("{:?}:", slot));
+		line(ctx, 24, y, color, Name::unwrap(names.get(*item)));
+		let mut bonuses = Vec::new();
+		if let Some(bonus) = power_bonus.get(*item) {
+			bonuses.push(format!("+{} power", bonus.power));
+		}
+		if let Some(bonus) = defense_bonus.get(*item) {
+			bonuses.push(format!("+{} defense", bonus.defense));
+		}
+		line(ctx, 48, y, Color::UiText, &bonuses.join(", "));
+		y += 1;
+	}
+	y += 1;
+
+	heading(ctx, 13, y, "Status");
+	y += 1;
+	let mut statuses = Vec::new();
+	if let Some(confused) = ecs.read_storage::<Confusion>().get(player) {
+		statuses.push((format!("Confused ({} turns)", confused.turns), Color::Confusion));
+	}
+	let carried = Weight::carried_by(
+		player,
+		&entities,
+		&ecs.read_storage::<InBackpack>(),
+		&ecs.read_storage::<Equipped>(),
+		&ecs.read_storage::<Weight>(),
+		&ecs.read_storage::<Stackable>(),
+	);
+	let capacity = stats.carry_capacity();
+	if carried > capacity {
+		statuses.push((format!("Overburdened ({:.1}/{:.1})", carried, capacity), Color::UiDanger));
+	} else {
+		statuses.push((format!("Carrying {:.1}/{:.1}", carried, capacity), Color::UiText));
+	}
+	if ecs.fetch::<WizardMode>().god_mode {
+		statuses.push(("God mode".to_string(), Color::UiHighlight));
+	}
+	for (status, color) in statuses {
+		line(ctx, 15, y, color, &status);
+		y += 1;
+	}
+	y += 1;
+
+	let run_stats = ecs.fetch::<RunStats>();
+	let purse = ecs.read_storage::<Purse>().get(player).map(|purse| purse.gold).unwrap_or_default();
+	heading(ctx, 13, y, "Progress");
+	y += 1;
+	let progress = [
+		format!("Depth:       {}", ecs.fetch::<Map>().depth),
+		format!("Turns:       {}", run_stats.turns),
+		format!("Kills:       {}", run_stats.total_kills()),
+		format!("Items used:  {}", run_stats.items_used),
+		format!("Gold:        {}", purse),
+	];
+	for text in progress.iter() {
+		line(ctx, 15, y, Color::UiText, text);
+		y += 1;
+	}
+
+	ctx.print_color(13, 42, Color::UiTitle.rgb(), Color::Background.rgb(), "ESCAPE to close");
+
+	match ctx.key {
+		Some(VirtualKeyCode::Escape | VirtualKeyCode::C | VirtualKeyCode::Return) => SummaryResult::Dismissed,
+		_ => SummaryResult::NoResponse,
+	}
+}
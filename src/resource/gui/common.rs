@@ -1,11 +1,13 @@
-use rltk::{Point, RGB};
+use rltk::Point;
 use specs::{Join, World, WorldExt};
 
 use crate::{
-    component::{CombatStats, Name, Player, Position},
-    resource::{gamelog, map},
+    component::{CombatStats, Name, Player, Position, Purse},
+    resource::{camera::Camera, gamelog, map, palette::Color},
 };
 
+use super::print_log_entry;
+
 pub fn player_healthbar(ecs: &World, ctx: &mut rltk::Rltk) {
     let combat_stats = ecs.read_storage::<CombatStats>();
     let players = ecs.read_storage::<Player>();
@@ -14,8 +16,8 @@ pub fn player_healthbar(ecs: &World, ctx: &mut rltk::Rltk) {
         ctx.print_color(
             12,
             43,
-            RGB::named(rltk::YELLOW),
-            RGB::named(rltk::BLACK),
+            Color::UiTitle.rgb(),
+            Color::Background.rgb(),
             &health,
         );
 
@@ -25,8 +27,8 @@ pub fn player_healthbar(ecs: &World, ctx: &mut rltk::Rltk) {
             51,
             stats.hp,
             stats.max_hp,
-            RGB::named(rltk::RED),
-            RGB::named(rltk::BLACK),
+            Color::UiDanger.rgb(),
+            Color::Background.rgb(),
         );
     }
 }
@@ -37,12 +39,27 @@ pub fn draw_depth(ecs: &World, ctx: &mut rltk::Rltk) {
     ctx.print_color(
         2,
         43,
-        RGB::named(rltk::YELLOW),
-        RGB::named(rltk::BLACK),
+        Color::UiTitle.rgb(),
+        Color::Background.rgb(),
         &depth,
     );
 }
 
+pub fn draw_gold(ecs: &World, ctx: &mut rltk::Rltk) {
+    let purses = ecs.read_storage::<Purse>();
+    let players = ecs.read_storage::<Player>();
+    for (_player, purse) in (&players, &purses).join() {
+        let gold = format!(" Gold: {} ", purse.gold);
+        ctx.print_color(
+            2,
+            49,
+            Color::Gold.rgb(),
+            Color::Background.rgb(),
+            &gold,
+        );
+    }
+}
+
 pub fn gamelog(ecs: &World, ctx: &mut rltk::Rltk) {
     let log = ecs.fetch::<gamelog::GameLog>();
     let mut y = 44;
@@ -50,7 +67,7 @@ pub fn gamelog(ecs: &World, ctx: &mut rltk::Rltk) {
         if y >= 49 {
             break;
         }
-        ctx.print(2, y, entry);
+        print_log_entry(ctx, 2, y, 77, entry);
         y += 1;
     }
 }
@@ -61,14 +78,17 @@ pub fn draw_tooltips(ecs: &World, ctx: &mut rltk::Rltk) {
     let positions = ecs.read_storage::<Position>();
 
     let mouse_pos = ctx.mouse_pos();
-    if mouse_pos.0 >= map.width || mouse_pos.1 >= map.height {
+    let Some(world) = Camera::new(ecs).to_world(Point::new(mouse_pos.0, mouse_pos.1)) else {
+        return;
+    };
+    if !map.in_bounds(world.x, world.y) {
         return;
     }
 
     let mut tooltip: Vec<String> = Vec::new();
     for (name, position) in (&names, &positions).join() {
         let idx = map.xy_idx(position.x, position.y);
-        if position.x == mouse_pos.0 && position.y == mouse_pos.1 && map.visible_tiles[idx] {
+        if position.x == world.x && position.y == world.y && map.visible_tiles[idx] {
             tooltip.push(name.name.to_string());
         }
     }
@@ -79,35 +99,35 @@ pub fn draw_tooltips(ecs: &World, ctx: &mut rltk::Rltk) {
 
     let width = tooltip.iter().map(|s| s.len()).max().unwrap_or_default() as i32 + 3;
 
-    const FG: (u8, u8, u8) = rltk::WHITE;
-    const BG: (u8, u8, u8) = rltk::GRAY10;
+    let fg = Color::UiText.rgb();
+    let bg = Color::UiPanel.rgb();
 
     if mouse_pos.0 > 40 {
         let arrow_pos = Point::new(mouse_pos.0 - 2, mouse_pos.1);
         let left_x = mouse_pos.0 - width;
         let mut y = mouse_pos.1;
         for s in tooltip.iter() {
-            ctx.print_color(left_x, y, FG, BG, s);
+            ctx.print_color(left_x, y, fg, bg, s);
             let padding = (width - s.len() as i32) - 1;
             for i in 0..padding {
-                ctx.print_color(arrow_pos.x - i, y, FG, BG, &" ".to_string());
+                ctx.print_color(arrow_pos.x - i, y, fg, bg, &" ".to_string());
             }
             y += 1;
         }
-        ctx.print_color(arrow_pos.x, arrow_pos.y, FG, BG, &"->".to_string());
+        ctx.print_color(arrow_pos.x, arrow_pos.y, fg, bg, &"->".to_string());
     } else {
         let arrow_pos = Point::new(mouse_pos.0 + 1, mouse_pos.1);
         let left_x = mouse_pos.0 + 3;
         let mut y = mouse_pos.1;
         for s in tooltip.iter() {
             let padding = (width - s.len() as i32) - 1;
-            ctx.print_color(left_x + padding - 1, y, FG, BG, s);
+            ctx.print_color(left_x + padding - 1, y, fg, bg, s);
             for i in 0..padding {
-                ctx.print_color(arrow_pos.x + 1 + i as i32, y, FG, BG, &" ".to_string());
+                ctx.print_color(arrow_pos.x + 1 + i as i32, y, fg, bg, &" ".to_string());
             }
             y += 1;
         }
-        ctx.print_color(arrow_pos.x, arrow_pos.y, FG, BG, &"<-".to_string());
+        ctx.print_color(arrow_pos.x, arrow_pos.y, fg, bg, &"<-".to_string());
     }
 }
 
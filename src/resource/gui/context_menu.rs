@@ -0,0 +1,141 @@
+use rltk::{Point, VirtualKeyCode};
+use specs::{Entity, World, WorldExt};
+
+use crate::{
+	component::*,
+	resource::{camera::{Camera, VIEW_HEIGHT, VIEW_WIDTH}, palette::Color, player::PlayerData},
+	utils::rect::Rect,
+};
+
+use super::item_label;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum ItemAction {
+	PickUp,
+	Use,
+	Equip,
+	Throw,
+	Drop,
+	Examine,
+}
+
+impl ItemAction {
+	pub fn label(self) -> &'static str {
+		match self {
+			ItemAction::PickUp => "Pick up",
+			ItemAction::Use => "Use",
+			ItemAction::Equip => "Equip",
+			ItemAction::Throw => "Throw",
+			ItemAction::Drop => "Drop",
+			ItemAction::Examine => "Examine",
+		}
+	}
+}
+
+/// What can be done with an item lying on the map, in menu order.
+pub const FLOOR_ACTIONS: [ItemAction; 3] = [ItemAction::PickUp, ItemAction::Use, ItemAction::Examine];
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum ContextMenuResult {
+	Cancel,
+	NoResponse { selection: usize },
+	Selected(ItemAction),
+}
+
+/// Whether `item` lies on the same tile as the player.
+pub fn underfoot(ecs: &World, item: Entity) -> bool {
+	let player = ecs.fetch::<PlayerData>().position;
+	ecs.read_storage::<Position>().get(item).is_some_and(|pos| pos.x == player.x && pos.y == player.y)
+}
+
+/// Items on the floor can only be used from under the player's feet; picking one up from
+/// further away walks over to it first.
+pub fn floor_action_available(ecs: &World, item: Entity, action: ItemAction) -> bool {
+	match action {
+		ItemAction::Use => underfoot(ecs, item) && !ecs.read_storage::<Gold>().contains(item),
+		_ => true,
+	}
+}
+
+/// Draws `actions` as lettered rows from `x`, `y` down and turns this frame's input into a
+/// result. Actions that are not `available` are greyed out and cannot be chosen, and a
+/// click outside `area` closes the menu.
+pub fn action_rows(
+	ctx: &mut rltk::Rltk,
+	x: i32,
+	y: i32,
+	width: i32,
+	area: Rect,
+	actions: &[(ItemAction, bool)],
+	selection: usize,
+) -> ContextMenuResult {
+	let (mouse_x, mouse_y) = ctx.mouse_pos();
+	let mut clicked = None;
+	for (row, (action, available)) in actions.iter().enumerate() {
+		let row_y = y + row as i32;
+		let hovered = mouse_y == row_y && mouse_x >= x && mouse_x < x + width;
+		let fg = if *available { Color::UiText } else { Color::UiDim };
+		let bg = if row == selection || hovered { Color::UiPanel } else { Color::Background };
+		ctx.print_color(x, row_y, Color::UiTitle.rgb(), bg.rgb(), format!("({})", (b'a' + row as u8) as char));
+		ctx.print_color(x + 3, row_y, fg.rgb(), bg.rgb(), format!(" {:<width$}", action.label(), width = (width - 4).max(0) as usize));
+		if hovered {
+			clicked = Some(row);
+		}
+	}
+
+	let choose = |row: usize| match actions[row] {
+		(action, true) => ContextMenuResult::Selected(action),
+		(_, false) => ContextMenuResult::NoResponse { selection: row },
+	};
+	if ctx.left_click {
+		let inside = mouse_x >= area.x1 && mouse_x <= area.x2 && mouse_y >= area.y1 && mouse_y <= area.y2;
+		return match clicked {
+			Some(row) => choose(row),
+			None if !inside => ContextMenuResult::Cancel,
+			None => ContextMenuResult::NoResponse { selection },
+		};
+	}
+
+	let count = actions.len();
+	match ctx.key {
+		Some(VirtualKeyCode::Escape) => ContextMenuResult::Cancel,
+		Some(VirtualKeyCode::Up | VirtualKeyCode::K) => ContextMenuResult::NoResponse { selection: (selection + count - 1) % count },
+		Some(VirtualKeyCode::Down | VirtualKeyCode::J) => ContextMenuResult::NoResponse { selection: (selection + 1) % count },
+		Some(VirtualKeyCode::Return) => choose(selection),
+		Some(key) => match rltk::letter_to_option(key) {
+			row if row >= 0 && (row as usize) < count => choose(row as usize),
+			_ => ContextMenuResult::NoResponse { selection },
+		},
+		None => ContextMenuResult::NoResponse { selection },
+	}
+}
+
+/// A small menu next to an item on the map, opened by right-clicking it. Actions that are
+/// not possible right now are greyed out.
+pub fn item_context_menu(ecs: &World, ctx: &mut rltk::Rltk, item: Entity, selection: usize) -> ContextMenuResult {
+	let Some(pos) = ecs.read_storage::<Position>().get(item).map(|pos| Point::new(pos.x, pos.y)) else {
+		return ContextMenuResult::Cancel;
+	};
+	let Some(screen) = Camera::new(ecs).to_screen(pos) else {
+		return ContextMenuResult::Cancel;
+	};
+	let names = ecs.read_storage::<Name>();
+	let Some(name) = names.get(item) else {
+		return ContextMenuResult::Cancel;
+	};
+	let title = item_label(name, ecs.read_storage::<Stackable>().get(item), ecs.read_storage::<Charges>().get(item));
+	let title_color = ecs.read_storage::<Renderable>().get(item).map(|render| render.fg).unwrap_or(Color::UiText);
+
+	let width = title.len().max(14) as i32 + 3;
+	let height = FLOOR_ACTIONS.len() as i32 + 1;
+	let x = if screen.x + 2 + width < VIEW_WIDTH { screen.x + 2 } else { screen.x - 2 - width };
+	let y = screen.y.min(VIEW_HEIGHT - 1 - height);
+	ctx.set_bg(screen.x, screen.y, Color::UiHighlight.rgb());
+	ctx.draw_box(x, y, width, height, Color::UiText.rgb(), Color::Background.rgb());
+	ctx.print_color(x + 2, y, title_color.rgb(), Color::Background.rgb(), &title);
+
+	let actions = FLOOR_ACTIONS.iter()
+		.map(|action| (*action, floor_action_available(ecs, item, *action)))
+		.collect::<Vec<_>>();
+	action_rows(ctx, x + 1, y + 1, width - 1, Rect::new(x, y, width, height), &actions, selection)
+}
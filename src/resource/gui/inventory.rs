@@ -1,50 +1,347 @@
-use rltk::{VirtualKeyCode, RGB};
-use specs::{Entity, Join, WorldExt};
+use std::cmp::Ordering;
 
-use crate::{component::{InBackpack, Name}, resource::player::PlayerEntity, state::State};
+use rltk::VirtualKeyCode;
+use specs::{Entity, Join, World, WorldExt};
+
+use crate::{component::*, resource::{palette::Color, player::PlayerEntity}, state::State, utils::rect::Rect};
+
+use super::{action_rows, item_details, wrap_text, ContextMenuResult, ItemAction, SummaryResult};
+
+pub const PAGE_SIZE: usize = 20;
 
 #[derive(Debug, PartialEq, Clone, Copy)]
 pub enum ItemMenuResult {
 	Cancel,
-	NoResponse, 
+	NoResponse,
 	Selected(Entity),
+	ChangeView(InventoryView),
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum AmountMenuResult {
+	Cancel,
+	NoResponse { amount: i32 },
+	Selected { amount: i32 },
 }
-pub fn show_inventory(state: &mut State, ctx: &mut rltk::Rltk, title: &str) -> ItemMenuResult {
+
+/// The groups the inventory is split into, in the order they are listed.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub enum ItemCategory {
+	Potions,
+	Scrolls,
+	WandsAndStaves,
+	Equipment,
+	Other,
+}
+
+impl ItemCategory {
+	pub fn of(ecs: &World, item: Entity) -> Self {
+		if ecs.read_storage::<Equippable>().contains(item) {
+			ItemCategory::Equipment
+		} else if ecs.read_storage::<Charges>().contains(item) {
+			ItemCategory::WandsAndStaves
+		} else if ecs.read_storage::<ProvidesHealing>().contains(item) {
+			ItemCategory::Potions
+		} else if ecs.read_storage::<Consumable>().contains(item) {
+			ItemCategory::Scrolls
+		} else {
+			ItemCategory::Other
+		}
+	}
+
+	pub fn label(self) -> &'static str {
+		match self {
+			ItemCategory::Potions => "Potions",
+			ItemCategory::Scrolls => "Scrolls",
+			ItemCategory::WandsAndStaves => "Wands & staves",
+			ItemCategory::Equipment => "Equipment",
+			ItemCategory::Other => "Other",
+		}
+	}
+}
+
+/// How items are ordered within their category.
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub enum InventorySort {
+	#[default]
+	Name,
+	/// Heaviest stacks first.
+	Weight,
+	/// Most valuable first.
+	Value,
+}
+
+impl InventorySort {
+	pub fn label(self) -> &'static str {
+		match self {
+			InventorySort::Name => "name",
+			InventorySort::Weight => "weight",
+			InventorySort::Value => "value",
+		}
+	}
+
+	pub fn next(self) -> Self {
+		match self {
+			InventorySort::Name => InventorySort::Weight,
+			InventorySort::Weight => InventorySort::Value,
+			InventorySort::Value => InventorySort::Name,
+		}
+	}
+}
+
+/// The page of the inventory on screen and how it is sorted.
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub struct InventoryView {
+	pub page: usize,
+	pub sort: InventorySort,
+}
+
+/// The page on screen, moved back to the last page if the rows no longer reach it, and how
+/// many pages `rows` fill.
+pub fn paginate(rows: usize, page: usize) -> (usize, usize) {
+	let pages = usize::max(1, rows.div_ceil(PAGE_SIZE));
+	(usize::min(page, pages - 1), pages)
+}
+
+/// The page that PAGE UP and PAGE DOWN, or the arrow keys, turn to.
+pub fn turn_page(key: Option<VirtualKeyCode>, page: usize, pages: usize) -> Option<usize> {
+	match key {
+		Some(VirtualKeyCode::PageDown | VirtualKeyCode::Right) if page + 1 < pages => Some(page + 1),
+		Some(VirtualKeyCode::PageUp | VirtualKeyCode::Left) if page > 0 => Some(page - 1),
+		_ => None,
+	}
+}
+
+/// Prints the page number right-aligned against `right`, if there is more than one page.
+pub fn print_page(ctx: &mut rltk::Rltk, right: i32, y: i32, page: usize, pages: usize) {
+	if pages > 1 {
+		let page_label = format!("<{}/{}>", page + 1, pages);
+		ctx.print_color(right - page_label.len() as i32, y, Color::UiTitle.rgb(), Color::Background.rgb(), &page_label);
+	}
+}
+
+pub fn item_label(name: &Name, stack: Option<&Stackable>, charges: Option<&Charges>) -> String {
+	match (stack, charges) {
+		(_, Some(charges)) if charges.current == 1 => format!("{} (1 charge)", name.name),
+		(_, Some(charges)) => format!("{} ({} charges)", name.name, charges.current),
+		(Some(stack), None) if stack.quantity > 1 => format!("{} x{}", name.name, stack.quantity),
+		_ => name.name.to_string(),
+	}
+}
+
+struct InventoryRow {
+	item: Entity,
+	label: String,
+	category: ItemCategory,
+	weight: f32,
+	value: i32,
+}
+
+/// The player's pack, grouped by category and sorted within each.
+fn inventory_rows(ecs: &World, sort: InventorySort) -> Vec<InventoryRow> {
+	let player_entity = ecs.fetch::<PlayerEntity>().0;
+	let names = ecs.read_storage::<Name>();
+	let backpack = ecs.read_storage::<InBackpack>();
+	let charges = ecs.read_storage::<Charges>();
+	let stacks = ecs.read_storage::<Stackable>();
+	let weights = ecs.read_storage::<Weight>();
+	let values = ecs.read_storage::<Value>();
+	let entities = ecs.entities();
+
+	let mut rows = (&entities, &backpack, &names).join()
+		.filter(|item| item.1.owner == player_entity)
+		.map(|(entity, _pack, name)| {
+			let quantity = stacks.get(entity).map(|stack| stack.quantity).unwrap_or(1);
+			InventoryRow {
+				item: entity,
+				label: item_label(name, stacks.get(entity), charges.get(entity)),
+				category: ItemCategory::of(ecs, entity),
+				weight: weights.get(entity).map(|w| w.weight * quantity as f32).unwrap_or_default(),
+				value: values.get(entity).map(|v| v.gold).unwrap_or_default(),
+			}
+		})
+		.collect::<Vec<_>>();
+	rows.sort_by(|a, b| {
+		let within = match sort {
+			InventorySort::Name => Ordering::Equal,
+			InventorySort::Weight => b.weight.total_cmp(&a.weight),
+			InventorySort::Value => b.value.cmp(&a.value),
+		};
+		a.category.cmp(&b.category).then(within).then_with(|| a.label.cmp(&b.label))
+	});
+	rows
+}
+
+pub fn show_inventory(state: &mut State, ctx: &mut rltk::Rltk, title: &str, view: InventoryView) -> ItemMenuResult {
 	let player_entity = state.ecs.fetch::<PlayerEntity>().0;
-	let names = state.ecs.read_storage::<Name>();
-	let backpack = state.ecs.read_storage::<InBackpack>();
-	let entities = state.ecs.entities();
-
-	let inventory = (&backpack, &names).join().filter(|item| item.0.owner == player_entity);
-	let count = inventory.count();
-
-	let mut y = (25 - (count / 2)) as i32;
-	ctx.draw_box(15, y-2, 31, (count+3) as i32, RGB::named(rltk::WHITE), RGB::named(rltk::BLACK));
-	ctx.print_color(18, y-2, RGB::named(rltk::YELLOW), RGB::named(rltk::BLACK), title);
-	ctx.print_color(18, y+count as i32+1, RGB::named(rltk::YELLOW), RGB::named(rltk::BLACK), "ESCAPE to cancel");
-
-	let mut equippable : Vec<Entity> = Vec::new();
-	let mut j = 0;
-	for (entity, _pack, name) in (&entities, &backpack, &names).join().filter(|item| item.1.owner == player_entity ) {
-		ctx.set(17, y, RGB::named(rltk::WHITE), RGB::named(rltk::BLACK), rltk::to_cp437('('));
-		ctx.set(18, y, RGB::named(rltk::YELLOW), RGB::named(rltk::BLACK), 97+j as rltk::FontCharType);
-		ctx.set(19, y, RGB::named(rltk::WHITE), RGB::named(rltk::BLACK), rltk::to_cp437(')'));
-
-		ctx.print(21, y, &name.name.to_string());
-		equippable.push(entity);
+	let carried = Weight::carried_by(
+		player_entity,
+		&state.ecs.entities(),
+		&state.ecs.read_storage::<InBackpack>(),
+		&state.ecs.read_storage::<Equipped>(),
+		&state.ecs.read_storage::<Weight>(),
+		&state.ecs.read_storage::<Stackable>(),
+	);
+	let capacity = state.ecs.read_storage::<CombatStats>().get(player_entity).map(|stats| stats.carry_capacity()).unwrap_or_default();
+
+	let inventory = inventory_rows(&state.ecs, view.sort);
+	let (page, pages) = paginate(inventory.len(), view.page);
+	let shown = inventory.iter().skip(page * PAGE_SIZE).take(PAGE_SIZE).collect::<Vec<_>>();
+	let count = shown.len();
+	let mut headings = shown.iter().map(|row| row.category).collect::<Vec<_>>();
+	headings.dedup();
+	let height = count + headings.len();
+
+	let mut y = (25 - (height / 2)) as i32;
+	ctx.draw_box(15, y-2, 40, (height+3) as i32, Color::UiText.rgb(), Color::Background.rgb());
+	ctx.print_color(18, y-2, Color::UiTitle.rgb(), Color::Background.rgb(), title);
+	let footer = format!("ESCAPE to cancel, TAB: by {}", view.sort.label());
+	ctx.print_color(18, y+height as i32+1, Color::UiTitle.rgb(), Color::Background.rgb(), &footer);
+	let burden = format!("{:.1}/{:.0} lbs", carried, capacity);
+	let burden_color = if carried > capacity { Color::UiDanger.rgb() } else { Color::UiTitle.rgb() };
+	ctx.print_color(54 - burden.len() as i32, y-2, burden_color, Color::Background.rgb(), &burden);
+	print_page(ctx, 54, y+height as i32+1, page, pages);
+
+	let mut category = None;
+	for (j, row) in shown.iter().enumerate() {
+		if category != Some(row.category) {
+			category = Some(row.category);
+			ctx.print_color(17, y, Color::UiDim.rgb(), Color::Background.rgb(), row.category.label());
+			y += 1;
+		}
+		ctx.set(17, y, Color::UiText.rgb(), Color::Background.rgb(), rltk::to_cp437('('));
+		ctx.set(18, y, Color::UiTitle.rgb(), Color::Background.rgb(), 97+j as rltk::FontCharType);
+		ctx.set(19, y, Color::UiText.rgb(), Color::Background.rgb(), rltk::to_cp437(')'));
+
+		ctx.print(21, y, &row.label);
+		let detail = match view.sort {
+			InventorySort::Name => String::new(),
+			InventorySort::Weight => format!("{:.1}", row.weight),
+			InventorySort::Value => format!("{}g", row.value),
+		};
+		ctx.print_color(54 - detail.len() as i32, y, Color::UiDim.rgb(), Color::Background.rgb(), &detail);
 		y += 1;
-		j += 1;
 	}
 
+	if let Some(page) = turn_page(ctx.key, page, pages) {
+		return ItemMenuResult::ChangeView(InventoryView { page, ..view });
+	}
 	match ctx.key {
 		None => ItemMenuResult::NoResponse,
 		Some(VirtualKeyCode::Escape) => ItemMenuResult::Cancel,
+		Some(VirtualKeyCode::Tab) => ItemMenuResult::ChangeView(InventoryView { page: 0, sort: view.sort.next() }),
 		Some(key) => {
 			let selection = rltk::letter_to_option(key);
 			if selection > -1 && selection < count as i32 {
-				return ItemMenuResult::Selected(equippable[selection as usize]);
+				return ItemMenuResult::Selected(shown[selection as usize].item);
 			}
 			ItemMenuResult::NoResponse
 		},
 	}
 }
+
+/// What can be done with an item in the pack, in menu order.
+pub fn inventory_actions(ecs: &World, item: Entity) -> Vec<ItemAction> {
+	let apply = if ecs.read_storage::<Equippable>().contains(item) { ItemAction::Equip } else { ItemAction::Use };
+	vec![apply, ItemAction::Throw, ItemAction::Drop, ItemAction::Examine]
+}
+
+/// Heading, description and stats of an item, as the action menu and examine screen show them.
+fn item_summary(ecs: &World, item: Entity, width: usize) -> Vec<(String, Color)> {
+	let name = match ecs.read_storage::<Name>().get(item) {
+		Some(name) => item_label(name, ecs.read_storage::<Stackable>().get(item), ecs.read_storage::<Charges>().get(item)),
+		None => Name::unwrap(None).to_string(),
+	};
+	let color = ecs.read_storage::<Renderable>().get(item).map(|render| render.fg).unwrap_or(Color::UiText);
+	let mut lines = vec![(name, color), (ItemCategory::of(ecs, item).label().to_string(), Color::UiDim), (String::new(), Color::UiText)];
+	if let Some(description) = ecs.read_storage::<Description>().get(item) {
+		lines.extend(wrap_text(&description.text, width).into_iter().map(|line| (line, Color::UiText)));
+		lines.push((String::new(), Color::UiText));
+	}
+	lines.extend(item_details(ecs, item).into_iter().map(|line| (line, Color::UiDim)));
+	lines
+}
+
+/// Opens on an item picked from the inventory: what it is and what can be done with it.
+pub fn item_action_menu(ecs: &World, ctx: &mut rltk::Rltk, item: Entity, selection: usize) -> ContextMenuResult {
+	if !ecs.read_storage::<InBackpack>().contains(item) {
+		return ContextMenuResult::Cancel;
+	}
+	let lines = item_summary(ecs, item, 44);
+	let actions = inventory_actions(ecs, item).into_iter().map(|action| (action, true)).collect::<Vec<_>>();
+	let height = (lines.len() + actions.len() + 1) as i32;
+	let y = 25 - height / 2;
+	ctx.draw_box(15, y - 1, 48, height + 1, Color::UiText.rgb(), Color::Background.rgb());
+	for (row, (line, color)) in lines.iter().enumerate() {
+		ctx.print_color(17, y + row as i32, color.rgb(), Color::Background.rgb(), line);
+	}
+	ctx.print_color(18, y + height, Color::UiTitle.rgb(), Color::Background.rgb(), "ESCAPE to go back");
+
+	let actions_y = y + lines.len() as i32 + 1;
+	action_rows(ctx, 17, actions_y, 44, Rect::new(15, y - 1, 48, height + 1), &actions, selection)
+}
+
+/// The full story on an item in the pack, including how it compares to what is worn in its slot.
+pub fn examine_item(ecs: &World, ctx: &mut rltk::Rltk, item: Entity) -> SummaryResult {
+	let mut lines = item_summary(ecs, item, 56);
+	if let Some(equip) = ecs.read_storage::<Equippable>().get(item) {
+		let player = ecs.fetch::<PlayerEntity>().0;
+		let names = ecs.read_storage::<Name>();
+		let power_bonus = ecs.read_storage::<MeleePowerBonus>();
+		let defense_bonus = ecs.read_storage::<DefenseBonus>();
+		let worn = (&ecs.entities(), &ecs.read_storage::<Equipped>()).join()
+			.find(|(_, worn)| worn.owner == player && worn.slot == equip.slot)
+			.map(|(worn, _)| worn);
+		lines.push((String::new(), Color::UiText));
+		match worn {
+			None => lines.push((format!("Nothing is worn as {:?}.", equip.slot), Color::UiText)),
+			Some(worn) => {
+				lines.push((format!("Replaces: {}", Name::unwrap(names.get(worn))), Color::UiText));
+				let power = |e: Entity| power_bonus.get(e).map(|b| b.power).unwrap_or_default();
+				let defense = |e: Entity| defense_bonus.get(e).map(|b| b.defense).unwrap_or_default();
+				for (stat, change) in [("power", power(item) - power(worn)), ("defense", defense(item) - defense(worn))] {
+					if change != 0 {
+						let color = if change > 0 { Color::Heal } else { Color::Damage };
+						lines.push((format!("{:+} {}", change, stat), color));
+					}
+				}
+			}
+		}
+	}
+
+	let height = lines.len() as i32 + 1;
+	let y = 25 - height / 2;
+	ctx.draw_box(9, y - 1, 61, height + 1, Color::UiText.rgb(), Color::Background.rgb());
+	ctx.print_color(12, y - 1, Color::UiHighlight.rgb(), Color::Background.rgb(), "Examine");
+	for (row, (line, color)) in lines.iter().enumerate() {
+		ctx.print_color(11, y + row as i32, color.rgb(), Color::Background.rgb(), line);
+	}
+	ctx.print_color(12, y + height, Color::UiTitle.rgb(), Color::Background.rgb(), "ESCAPE to go back");
+
+	match ctx.key {
+		Some(VirtualKeyCode::Escape | VirtualKeyCode::Return) => SummaryResult::Dismissed,
+		_ => SummaryResult::NoResponse,
+	}
+}
+
+pub fn drop_amount(state: &mut State, ctx: &mut rltk::Rltk, item: Entity, amount: i32) -> AmountMenuResult {
+	let names = state.ecs.read_storage::<Name>();
+	let stacks = state.ecs.read_storage::<Stackable>();
+	let quantity = stacks.get(item).map(|s| s.quantity).unwrap_or(1);
+	let amount = amount.clamp(1, quantity);
+
+	let title = format!("Drop how many {}?", Name::unwrap(names.get(item)));
+	ctx.draw_box(15, 22, 31, 4, Color::UiText.rgb(), Color::Background.rgb());
+	ctx.print_color(18, 22, Color::UiTitle.rgb(), Color::Background.rgb(), &title);
+	let counter = format!("< {} / {} >", amount, quantity);
+	ctx.print_color(31 - counter.len() as i32 / 2, 24, Color::UiText.rgb(), Color::Background.rgb(), &counter);
+	ctx.print_color(18, 26, Color::UiTitle.rgb(), Color::Background.rgb(), "ENTER to drop");
+
+	match ctx.key {
+		Some(VirtualKeyCode::Escape) => AmountMenuResult::Cancel,
+		Some(VirtualKeyCode::Return) => AmountMenuResult::Selected { amount },
+		Some(VirtualKeyCode::Left | VirtualKeyCode::Down) => AmountMenuResult::NoResponse { amount: i32::max(1, amount - 1) },
+		Some(VirtualKeyCode::Right | VirtualKeyCode::Up) => AmountMenuResult::NoResponse { amount: i32::min(quantity, amount + 1) },
+		_ => AmountMenuResult::NoResponse { amount },
+	}
+}
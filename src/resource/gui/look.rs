@@ -0,0 +1,214 @@
+use rltk::{Point, VirtualKeyCode};
+use specs::{Entity, Join, World, WorldExt};
+
+use crate::{
+	component::*,
+	resource::{camera::Camera, map::{Map, TileType}, palette::Color, player::PlayerEntity},
+};
+
+const PANEL_WIDTH: i32 = 32;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum LookResult {
+	Cancel,
+	NoResponse,
+	Moved(Point),
+}
+
+/// How hurt a creature looks, without giving away its exact hit points.
+pub fn health_estimate(stats: &CombatStats) -> (&'static str, Color) {
+	let ratio = stats.hp as f32 / stats.max_hp as f32;
+	if stats.hp >= stats.max_hp {
+		("unhurt", Color::UiText)
+	} else if ratio >= 0.75 {
+		("lightly wounded", Color::UiText)
+	} else if ratio >= 0.5 {
+		("wounded", Color::UiTitle)
+	} else if ratio >= 0.25 {
+		("badly wounded", Color::UiDanger)
+	} else {
+		("nearly dead", Color::UiDanger)
+	}
+}
+
+/// One line per mechanical property of an item: what it does, how much is left, what it is worth.
+pub fn item_details(ecs: &World, item: Entity) -> Vec<String> {
+	let mut details = Vec::new();
+	if let Some(equip) = ecs.read_storage::<Equippable>().get(item) {
+		details.push(format!("Equipped as: {:?}", equip.slot));
+	}
+	if let Some(bonus) = ecs.read_storage::<MeleePowerBonus>().get(item) {
+		details.push(format!("Power bonus: +{}", bonus.power));
+	}
+	if let Some(bonus) = ecs.read_storage::<DefenseBonus>().get(item) {
+		details.push(format!("Defense bonus: +{}", bonus.defense));
+	}
+	if let Some(healing) = ecs.read_storage::<ProvidesHealing>().get(item) {
+		details.push(format!("Heals {} hp", healing.heal_amount));
+	}
+	if let Some(damage) = ecs.read_storage::<InflictsDamage>().get(item) {
+		details.push(format!("Deals {} damage", damage.damage));
+	}
+	if let Some(confusion) = ecs.read_storage::<Confusion>().get(item) {
+		details.push(format!("Confuses for {} turns", confusion.turns));
+	}
+	if let Some(ranged) = ecs.read_storage::<Ranged>().get(item) {
+		details.push(format!("Range: {}", ranged.range));
+	}
+	if let Some(aoe) = ecs.read_storage::<AreaOfEffect>().get(item) {
+		details.push(format!("Blast radius: {}", aoe.radius));
+	}
+	if ecs.read_storage::<Recharge>().contains(item) {
+		details.push("Restores wand and staff charges".to_string());
+	}
+	if let Some(charges) = ecs.read_storage::<Charges>().get(item) {
+		let fate = if charges.crumbles { ", crumbles when empty" } else { "" };
+		details.push(format!("Charges: {}/{}{}", charges.current, charges.max, fate));
+	}
+	if let Some(stack) = ecs.read_storage::<Stackable>().get(item) {
+		details.push(format!("Quantity: {}", stack.quantity));
+	}
+	if let Some(gold) = ecs.read_storage::<Gold>().get(item) {
+		details.push(format!("Amount: {} gold", gold.amount));
+	}
+	if let Some(weight) = ecs.read_storage::<Weight>().get(item) {
+		details.push(format!("Weight: {}", weight.weight));
+	}
+	if let Some(value) = ecs.read_storage::<Value>().get(item) {
+		details.push(format!("Worth: {} gold", value.gold));
+	}
+	details
+}
+
+/// Breaks `text` into lines of at most `width` characters, on spaces where possible.
+pub fn wrap_text(text: &str, width: usize) -> Vec<String> {
+	let mut lines = Vec::new();
+	let mut line = String::new();
+	for word in text.split_whitespace() {
+		if !line.is_empty() && line.len() + 1 + word.len() > width {
+			lines.push(std::mem::take(&mut line));
+		}
+		if !line.is_empty() {
+			line.push(' ');
+		}
+		line.push_str(word);
+	}
+	if !line.is_empty() {
+		lines.push(line);
+	}
+	lines
+}
+
+fn terrain(tile: TileType) -> (&'static str, &'static str) {
+	match tile {
+		TileType::Floor => ("Floor", "Worn flagstones."),
+		TileType::Wall => ("Wall", "Solid rock, roughly hewn."),
+		TileType::DownStairs => ("Stairs down", "A stairway leading deeper into the dungeon."),
+	}
+}
+
+/// Everything there is to say about the tile at `target`, as lines of coloured text.
+fn describe(ecs: &World, target: Point) -> Vec<(String, Color)> {
+	let map = ecs.fetch::<Map>();
+	let width = PANEL_WIDTH as usize - 4;
+	let mut lines = Vec::new();
+	let idx = map.xy_idx(target.x, target.y);
+	if !map.revealed_tiles[idx] {
+		lines.push(("Unexplored".to_string(), Color::UiDim));
+		return lines;
+	}
+
+	let (tile_name, tile_description) = terrain(map.tiles[idx]);
+	let seen = if map.visible_tiles[idx] { "" } else { " (remembered)" };
+	lines.push((format!("{}{}", tile_name, seen), Color::UiTitle));
+	lines.extend(wrap_text(tile_description, width).into_iter().map(|line| (line, Color::UiDim)));
+	if !map.visible_tiles[idx] {
+		return lines;
+	}
+
+	let entities = ecs.entities();
+	let positions = ecs.read_storage::<Position>();
+	let renderables = ecs.read_storage::<Renderable>();
+	let names = ecs.read_storage::<Name>();
+	let descriptions = ecs.read_storage::<Description>();
+	let combat_stats = ecs.read_storage::<CombatStats>();
+	let items = ecs.read_storage::<Item>();
+	let confusion = ecs.read_storage::<Confusion>();
+	let player = ecs.fetch::<PlayerEntity>().0;
+
+	let mut here = (&entities, &positions, &names).join()
+		.filter(|(_, pos, _)| pos.x == target.x && pos.y == target.y)
+		.map(|(entity, _, name)| (entity, name))
+		.collect::<Vec<_>>();
+	here.sort_by_key(|(entity, _)| renderables.get(*entity).map(|render| render.render_order).unwrap_or_default());
+
+	for (entity, name) in here {
+		lines.push((String::new(), Color::UiText));
+		let color = renderables.get(entity).map(|render| render.fg).unwrap_or(Color::UiText);
+		lines.push((name.name.clone(), color));
+		if let Some(description) = descriptions.get(entity) {
+			lines.extend(wrap_text(&description.text, width).into_iter().map(|line| (line, Color::UiText)));
+		}
+		if let Some(stats) = combat_stats.get(entity) {
+			if entity == player {
+				lines.push((format!("HP: {}/{}", stats.hp, stats.max_hp), Color::UiText));
+			} else {
+				let (estimate, color) = health_estimate(stats);
+				lines.push((format!("Looks {}", estimate), color));
+			}
+		}
+		if items.contains(entity) {
+			lines.extend(item_details(ecs, entity).into_iter().map(|line| (line, Color::UiDim)));
+		} else if let Some(confused) = confusion.get(entity) {
+			lines.push((format!("Confused ({} turns)", confused.turns), Color::Confusion));
+		}
+		if ecs.read_storage::<Boss>().contains(entity) {
+			lines.push(("Regenerates and breathes fire".to_string(), Color::UiDanger));
+		}
+		if ecs.read_storage::<Vendor>().contains(entity) {
+			lines.push(("Will buy and sell".to_string(), Color::UiDim));
+		}
+	}
+	lines
+}
+
+/// Moves a cursor over the map with the direction keys and describes whatever is under it.
+pub fn look_cursor(ecs: &World, ctx: &mut rltk::Rltk, cursor: Point) -> LookResult {
+	let camera = Camera::new(ecs);
+	ctx.print_color(5, 0, Color::UiTitle.rgb(), Color::Background.rgb(), "Look: direction keys to move, ESCAPE to stop");
+
+	let Some(screen) = camera.to_screen(cursor) else {
+		return LookResult::Cancel;
+	};
+	ctx.set_bg(screen.x, screen.y, Color::UiHighlight.rgb());
+
+	let mut lines = describe(ecs, cursor);
+	lines.truncate(38);
+	let x = if screen.x < 40 { 79 - PANEL_WIDTH } else { 0 };
+	let height = lines.len() as i32 + 1;
+	ctx.draw_box(x, 2, PANEL_WIDTH, height, Color::UiText.rgb(), Color::Background.rgb());
+	for (row, (line, color)) in lines.iter().enumerate() {
+		ctx.print_color(x + 2, 3 + row as i32, color.rgb(), Color::Background.rgb(), line);
+	}
+
+	let delta = match ctx.key {
+		None => return LookResult::NoResponse,
+		Some(VirtualKeyCode::Escape | VirtualKeyCode::X | VirtualKeyCode::Return) => return LookResult::Cancel,
+		Some(VirtualKeyCode::Left | VirtualKeyCode::Numpad4 | VirtualKeyCode::H) => (-1, 0),
+		Some(VirtualKeyCode::Right | VirtualKeyCode::Numpad6 | VirtualKeyCode::L) => (1, 0),
+		Some(VirtualKeyCode::Up | VirtualKeyCode::Numpad8 | VirtualKeyCode::K) => (0, -1),
+		Some(VirtualKeyCode::Down | VirtualKeyCode::Numpad2 | VirtualKeyCode::J) => (0, 1),
+		Some(VirtualKeyCode::Numpad9 | VirtualKeyCode::Z) => (1, -1),
+		Some(VirtualKeyCode::Numpad7 | VirtualKeyCode::U) => (-1, -1),
+		Some(VirtualKeyCode::Numpad3 | VirtualKeyCode::N) => (1, 1),
+		Some(VirtualKeyCode::Numpad1 | VirtualKeyCode::B) => (-1, 1),
+		Some(_) => return LookResult::NoResponse,
+	};
+	let target = Point::new(cursor.x + delta.0, cursor.y + delta.1);
+	let map = ecs.fetch::<Map>();
+	if map.in_bounds(target.x, target.y) && camera.to_screen(target).is_some() {
+		LookResult::Moved(target)
+	} else {
+		LookResult::NoResponse
+	}
+}
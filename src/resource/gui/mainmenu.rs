@@ -1,11 +1,13 @@
-use rltk::{VirtualKeyCode, RGB};
+use rltk::VirtualKeyCode;
 
-use crate::{state::State, system::saveload_system};
+use crate::{resource::palette::Color, state::State, system::saveload_system};
 
 #[derive(PartialEq, Clone, Copy, Debug, Default)]
 pub enum MainMenuSelection {
 	#[default] NewGame, 
 	LoadGame, 
+	HighScores,
+	Options,
 	Quit
 }
 
@@ -20,7 +22,7 @@ pub fn main_menu(state: &mut State, ctx: &mut rltk::Rltk, selection: MainMenuSel
 	let save_exists = saveload_system::has_save_game();
 	ctx.print_color_centered(
 		15, 
-		RGB::named(rltk::YELLOW), RGB::named(rltk::BLACK), 
+		Color::UiTitle.rgb(), Color::Background.rgb(), 
 		"Rust Roguelike Tutorial"
 	);
 
@@ -28,7 +30,9 @@ pub fn main_menu(state: &mut State, ctx: &mut rltk::Rltk, selection: MainMenuSel
 	if save_exists {
 		menu_item(25, MainMenuSelection::LoadGame, selection, "Load Game", ctx);
 	}
-	menu_item(26, MainMenuSelection::Quit, selection, "Quit", ctx);
+	menu_item(26, MainMenuSelection::HighScores, selection, "High Scores", ctx);
+	menu_item(27, MainMenuSelection::Options, selection, "Options", ctx);
+	menu_item(28, MainMenuSelection::Quit, selection, "Quit", ctx);
 
 	match ctx.key {
 		Some(VirtualKeyCode::Escape) => MainMenuResult::NoSelection{ selected: MainMenuSelection::Quit },
@@ -42,7 +46,9 @@ pub fn main_menu(state: &mut State, ctx: &mut rltk::Rltk, selection: MainMenuSel
 fn selection_next(selection: MainMenuSelection, save_exists: bool) -> MainMenuSelection {
 	let selection = match selection {
 		MainMenuSelection::NewGame => MainMenuSelection::LoadGame,
-		MainMenuSelection::LoadGame => MainMenuSelection::Quit,
+		MainMenuSelection::LoadGame => MainMenuSelection::HighScores,
+		MainMenuSelection::HighScores => MainMenuSelection::Options,
+		MainMenuSelection::Options => MainMenuSelection::Quit,
 		MainMenuSelection::Quit => MainMenuSelection::NewGame,
 	};
 	if !save_exists && selection == MainMenuSelection::LoadGame {
@@ -56,7 +62,9 @@ fn selection_prev(selection: MainMenuSelection, save_exists: bool) -> MainMenuSe
 	let selection = match selection {
 		MainMenuSelection::NewGame => MainMenuSelection::Quit,
 		MainMenuSelection::LoadGame => MainMenuSelection::NewGame,
-		MainMenuSelection::Quit => MainMenuSelection::LoadGame,
+		MainMenuSelection::HighScores => MainMenuSelection::LoadGame,
+		MainMenuSelection::Options => MainMenuSelection::HighScores,
+		MainMenuSelection::Quit => MainMenuSelection::Options,
 	};
 	if !save_exists && selection == MainMenuSelection::LoadGame {
 		selection_prev(selection, save_exists)
@@ -66,6 +74,6 @@ fn selection_prev(selection: MainMenuSelection, save_exists: bool) -> MainMenuSe
 }
 
 fn menu_item(y: i32, item: MainMenuSelection, selected: MainMenuSelection, title: &str, ctx: &mut rltk::Rltk) {
-	let color = if item == selected { RGB::named(rltk::MAGENTA) } else { RGB::named(rltk::WHITE) };
-	ctx.print_color_centered(y, color, RGB::named(rltk::BLACK), title)
+	let color = if item == selected { Color::UiHighlight.rgb() } else { Color::UiText.rgb() };
+	ctx.print_color_centered(y, color, Color::Background.rgb(), title)
 }
\ No newline at end of file
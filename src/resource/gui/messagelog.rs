@@ -0,0 +1,63 @@
+use rltk::VirtualKeyCode;
+use specs::World;
+
+use crate::resource::{gamelog::{GameLog, LogEntry}, palette::Color, tileset::{self, GraphicsMode}};
+
+/// Rows of messages the history viewer shows at once.
+const HISTORY_ROWS: usize = 45;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum MessageLogResult {
+	Close,
+	/// `offset` is how many messages the view is scrolled back from the newest one.
+	NoResponse { offset: usize },
+}
+
+/// Prints one log entry at `x`, `y` with its turn stamp, cut off at `width` columns.
+pub fn print_log_entry(ctx: &mut rltk::Rltk, x: i32, y: i32, width: i32, entry: &LogEntry) {
+	let mut fragments = vec![(format!("{:>5} ", entry.turn), Color::UiDim)];
+	fragments.extend(entry.fragments.iter().map(|fragment| (fragment.text.clone(), fragment.color)));
+	if entry.count > 1 {
+		fragments.push((format!(" x{}", entry.count), Color::UiTitle));
+	}
+
+	let mut column = 0;
+	for (text, color) in fragments {
+		let room = (width - column).max(0) as usize;
+		let text = text.chars().take(room).collect::<String>();
+		ctx.print_color(x + column, y, color.rgb(), Color::Background.rgb(), &text);
+		column += text.chars().count() as i32;
+	}
+}
+
+pub fn show_message_log(ecs: &World, ctx: &mut rltk::Rltk, offset: usize) -> MessageLogResult {
+	let log = ecs.fetch::<GameLog>();
+	let max_offset = log.entries.len().saturating_sub(HISTORY_ROWS);
+	let offset = offset.min(max_offset);
+
+	tileset::clear_layers(ctx, *ecs.fetch::<GraphicsMode>());
+	ctx.draw_box(0, 0, 79, 49, Color::UiText.rgb(), Color::Background.rgb());
+	ctx.print_color(3, 0, Color::UiTitle.rgb(), Color::Background.rgb(), "Message log");
+	ctx.print_color(3, 49, Color::UiTitle.rgb(), Color::Background.rgb(), "UP/DOWN, PGUP/PGDN, HOME/END to scroll, ESCAPE to close");
+	if max_offset > 0 {
+		let position = format!("{}/{}", log.entries.len() - offset, log.entries.len());
+		ctx.print_color(77 - position.len() as i32, 0, Color::UiTitle.rgb(), Color::Background.rgb(), &position);
+	}
+
+	let end = log.entries.len() - offset;
+	let start = end.saturating_sub(HISTORY_ROWS);
+	for (row, entry) in log.entries[start..end].iter().enumerate() {
+		print_log_entry(ctx, 2, 2 + row as i32, 76, entry);
+	}
+
+	match ctx.key {
+		Some(VirtualKeyCode::Escape | VirtualKeyCode::M) => MessageLogResult::Close,
+		Some(VirtualKeyCode::Up | VirtualKeyCode::K) => MessageLogResult::NoResponse { offset: (offset + 1).min(max_offset) },
+		Some(VirtualKeyCode::Down | VirtualKeyCode::J) => MessageLogResult::NoResponse { offset: offset.saturating_sub(1) },
+		Some(VirtualKeyCode::PageUp) => MessageLogResult::NoResponse { offset: (offset + HISTORY_ROWS).min(max_offset) },
+		Some(VirtualKeyCode::PageDown) => MessageLogResult::NoResponse { offset: offset.saturating_sub(HISTORY_ROWS) },
+		Some(VirtualKeyCode::Home) => MessageLogResult::NoResponse { offset: max_offset },
+		Some(VirtualKeyCode::End) => MessageLogResult::NoResponse { offset: 0 },
+		_ => MessageLogResult::NoResponse { offset },
+	}
+}
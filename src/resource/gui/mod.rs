@@ -1,15 +1,34 @@
-use rltk::RGB;
 use specs::World;
 
+use crate::resource::palette::Color;
+
+pub mod character;
 pub mod common;
+pub mod context_menu;
 pub mod inventory;
+pub mod look;
 pub mod mainmenu;
+pub mod messagelog;
+pub mod options;
+pub mod overlay;
+pub mod summary;
 pub mod targeting;
+pub mod vendor;
+pub mod wizard;
 
+pub use character::*;
 pub use common::*;
+pub use context_menu::*;
 pub use inventory::*;
+pub use look::*;
 pub use mainmenu::*;
+pub use messagelog::*;
+pub use options::*;
+pub use overlay::*;
+pub use summary::*;
 pub use targeting::*;
+pub use vendor::*;
+pub use wizard::*;
 
 pub fn draw_ui(ecs: &World, ctx: &mut rltk::Rltk) {
     ctx.draw_box(
@@ -17,16 +36,17 @@ pub fn draw_ui(ecs: &World, ctx: &mut rltk::Rltk) {
         43,
         79,
         6,
-        RGB::named(rltk::WHITE),
-        RGB::named(rltk::BLACK),
+        Color::UiText.rgb(),
+        Color::Background.rgb(),
     );
 
     player_healthbar(ecs, ctx);
     gamelog(ecs, ctx);
 
     let mouse_pos = ctx.mouse_pos();
-    ctx.set_bg(mouse_pos.0, mouse_pos.1, RGB::named(rltk::MAGENTA));
+    ctx.set_bg(mouse_pos.0, mouse_pos.1, Color::UiHighlight.rgb());
 
     draw_tooltips(ecs, ctx);
     draw_depth(ecs, ctx);
+    draw_gold(ecs, ctx);
 }
@@ -0,0 +1,34 @@
+use rltk::VirtualKeyCode;
+
+use crate::resource::{palette::Color, settings::{Settings, OPTIONS}};
+
+#[derive(PartialEq, Copy, Clone)]
+pub enum OptionsResult {
+	NoResponse { selected: usize },
+	Change { selected: usize, forward: bool },
+	Back,
+}
+
+pub fn options_menu(ctx: &mut rltk::Rltk, settings: &Settings, selection: usize) -> OptionsResult {
+	ctx.print_color_centered(15, Color::UiTitle.rgb(), Color::Background.rgb(), "Options");
+
+	for (i, option) in OPTIONS.iter().enumerate() {
+		let y = 20 + i as i32;
+		let color = if i == selection { Color::UiHighlight.rgb() } else { Color::UiText.rgb() };
+		ctx.print_color(20, y, color, Color::Background.rgb(), option.name());
+		ctx.print_color(45, y, color, Color::Background.rgb(), settings.value(*option));
+	}
+
+	let hint = if OPTIONS[selection].needs_restart() { "Takes effect on the next start" } else { "" };
+	ctx.print_color_centered(30, Color::UiDim.rgb(), Color::Background.rgb(), hint);
+	ctx.print_color_centered(32, Color::UiTitle.rgb(), Color::Background.rgb(), "LEFT/RIGHT to change, ESCAPE to go back");
+
+	match ctx.key {
+		Some(VirtualKeyCode::Escape) => OptionsResult::Back,
+		Some(VirtualKeyCode::Up) => OptionsResult::NoResponse { selected: (selection + OPTIONS.len() - 1) % OPTIONS.len() },
+		Some(VirtualKeyCode::Down) => OptionsResult::NoResponse { selected: (selection + 1) % OPTIONS.len() },
+		Some(VirtualKeyCode::Left) => OptionsResult::Change { selected: selection, forward: false },
+		Some(VirtualKeyCode::Right | VirtualKeyCode::Return) => OptionsResult::Change { selected: selection, forward: true },
+		_ => OptionsResult::NoResponse { selected: selection },
+	}
+}
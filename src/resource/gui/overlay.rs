@@ -0,0 +1,109 @@
+use rltk::{Point, VirtualKeyCode, RGB};
+use specs::{Join, World, WorldExt};
+
+use crate::{component::{Monster, Viewshed}, resource::{camera::Camera, map::Map, overlay::{DebugOverlays, Overlay}}};
+
+struct LegendEntry {
+	overlay: Overlay,
+	key: VirtualKeyCode,
+	label: &'static str,
+	color: (u8, u8, u8),
+}
+
+const OVERLAYS: [LegendEntry; 5] = [
+	LegendEntry { overlay: Overlay::Blocked, key: VirtualKeyCode::F1, label: "F1 blocked", color: rltk::DARK_RED },
+	LegendEntry { overlay: Overlay::TileContent, key: VirtualKeyCode::F2, label: "F2 tile content", color: rltk::YELLOW },
+	LegendEntry { overlay: Overlay::Viewsheds, key: VirtualKeyCode::F3, label: "F3 monster sight", color: rltk::NAVY },
+	LegendEntry { overlay: Overlay::Paths, key: VirtualKeyCode::F4, label: "F4 monster paths", color: rltk::MAGENTA },
+	LegendEntry { overlay: Overlay::Rooms, key: VirtualKeyCode::F5, label: "F5 rooms", color: rltk::DARK_GREEN },
+];
+
+pub fn overlay_for_key(key: VirtualKeyCode) -> Option<Overlay> {
+	OVERLAYS.iter().find(|entry| entry.key == key).map(|entry| entry.overlay)
+}
+
+fn overlay_color(overlay: Overlay) -> RGB {
+	let entry = OVERLAYS.iter().find(|entry| entry.overlay == overlay).expect("Overlay without a legend entry");
+	RGB::named(entry.color)
+}
+
+fn tint(ctx: &mut rltk::Rltk, camera: &Camera, x: i32, y: i32, color: RGB) {
+	if let Some(screen) = camera.to_screen(Point::new(x, y)) {
+		ctx.set_bg(screen.x, screen.y, color);
+	}
+}
+
+fn mark(ctx: &mut rltk::Rltk, camera: &Camera, x: i32, y: i32, color: RGB, glyph: char) {
+	if let Some(screen) = camera.to_screen(Point::new(x, y)) {
+		ctx.set(screen.x, screen.y, color, RGB::named(rltk::BLACK), rltk::to_cp437(glyph));
+	}
+}
+
+/// Draws the active debug overlays on top of the map, with a legend in the corner.
+pub fn draw_overlays(ecs: &World, ctx: &mut rltk::Rltk) {
+	let overlays = ecs.fetch::<DebugOverlays>();
+	if overlays.active.is_empty() { return; }
+	let map = ecs.fetch::<Map>();
+	let camera = Camera::new(ecs);
+
+	if overlays.is_active(Overlay::Rooms) {
+		let color = overlay_color(Overlay::Rooms);
+		for (i, room) in map.rooms.iter().enumerate() {
+			for x in room.x1..=room.x2 + 1 {
+				tint(ctx, &camera, x, room.y1, color);
+				tint(ctx, &camera, x, room.y2 + 1, color);
+			}
+			for y in room.y1..=room.y2 + 1 {
+				tint(ctx, &camera, room.x1, y, color);
+				tint(ctx, &camera, room.x2 + 1, y, color);
+			}
+			if let Some(corner) = camera.to_screen(Point::new(room.x1, room.y1)) {
+				ctx.print_color(corner.x, corner.y, RGB::named(rltk::WHITE), color, i.to_string());
+			}
+		}
+	}
+
+	if overlays.is_active(Overlay::Viewsheds) {
+		let color = overlay_color(Overlay::Viewsheds);
+		let monsters = ecs.read_storage::<Monster>();
+		let viewsheds = ecs.read_storage::<Viewshed>();
+		for (_monster, viewshed) in (&monsters, &viewsheds).join() {
+			for tile in viewshed.visible_tiles.iter() {
+				tint(ctx, &camera, tile.x, tile.y, color);
+			}
+		}
+	}
+
+	if overlays.is_active(Overlay::Blocked) {
+		let color = overlay_color(Overlay::Blocked);
+		for (idx, _) in map.blocked.iter().enumerate().filter(|(_, blocked)| **blocked) {
+			tint(ctx, &camera, idx as i32 % map.width, idx as i32 / map.width, color);
+		}
+	}
+
+	if overlays.is_active(Overlay::Paths) {
+		let color = overlay_color(Overlay::Paths);
+		for path in overlays.monster_paths.values() {
+			for idx in path.iter().skip(1) {
+				mark(ctx, &camera, *idx as i32 % map.width, *idx as i32 / map.width, color, '*');
+			}
+		}
+	}
+
+	if overlays.is_active(Overlay::TileContent) {
+		let color = overlay_color(Overlay::TileContent);
+		for (idx, content) in map.tile_content.iter().enumerate().filter(|(_, content)| !content.is_empty()) {
+			let glyph = if content.len() > 9 { '+' } else { (b'0' + content.len() as u8) as char };
+			mark(ctx, &camera, idx as i32 % map.width, idx as i32 / map.width, color, glyph);
+		}
+	}
+
+	let x = 79 - OVERLAYS.iter().map(|entry| entry.label.len() as i32).max().unwrap_or_default() - 4;
+	ctx.draw_box(x, 1, 79 - x - 1, OVERLAYS.len() as i32 + 1, RGB::named(rltk::WHITE), RGB::named(rltk::BLACK));
+	for (j, entry) in OVERLAYS.iter().enumerate() {
+		let y = 2 + j as i32;
+		let fg = if overlays.is_active(entry.overlay) { RGB::named(rltk::WHITE) } else { RGB::named(rltk::GREY) };
+		ctx.set_bg(x + 1, y, RGB::named(entry.color));
+		ctx.print_color(x + 3, y, fg, RGB::named(rltk::BLACK), entry.label);
+	}
+}
@@ -0,0 +1,100 @@
+use rltk::VirtualKeyCode;
+use specs::World;
+
+use crate::{resource::{gamelog::GameLog, map::Map, palette::Color, run_stats::RunStats, tileset::{self, GraphicsMode}}, system::highscore};
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum SummaryResult {
+	NoResponse,
+	Dismissed,
+}
+
+/// Prints depth, turns, items used and kills by type starting at `y`, returning the next free row.
+pub fn run_summary(ecs: &World, ctx: &mut rltk::Rltk, mut y: i32) -> i32 {
+	let stats = ecs.fetch::<RunStats>();
+	let depth = ecs.fetch::<Map>().depth;
+
+	let lines = [
+		format!("Depth reached: {}", depth),
+		format!("Turns taken: {}", stats.turns),
+		format!("Items used: {}", stats.items_used),
+		format!("Monsters slain: {}", stats.total_kills()),
+	];
+	for line in lines.iter() {
+		ctx.print_color_centered(y, Color::UiText.rgb(), Color::Background.rgb(), line);
+		y += 1;
+	}
+	for (name, count) in stats.kills.iter() {
+		ctx.print_color_centered(y, Color::UiDim.rgb(), Color::Background.rgb(), format!("{} x{}", name, count));
+		y += 1;
+	}
+	y
+}
+
+pub fn victory_screen(ecs: &World, ctx: &mut rltk::Rltk) -> SummaryResult {
+	tileset::clear_layers(ctx, *ecs.fetch::<GraphicsMode>());
+	ctx.print_color_centered(8, Color::Gold.rgb(), Color::Background.rgb(), "Victory!");
+	ctx.print_color_centered(10, Color::UiText.rgb(), Color::Background.rgb(), "The Amulet of the Ancients is yours, and the dungeon falls silent.");
+	let y = run_summary(ecs, ctx, 13);
+	ctx.print_color_centered(y + 2, Color::UiHighlight.rgb(), Color::Background.rgb(), "Press ENTER to return to the menu");
+
+	match ctx.key {
+		Some(VirtualKeyCode::Return | VirtualKeyCode::Escape) => SummaryResult::Dismissed,
+		_ => SummaryResult::NoResponse,
+	}
+}
+
+pub fn game_over_screen(ecs: &World, ctx: &mut rltk::Rltk) -> SummaryResult {
+	tileset::clear_layers(ctx, *ecs.fetch::<GraphicsMode>());
+	ctx.print_color_centered(6, Color::UiDanger.rgb(), Color::Background.rgb(), "You are dead.");
+	let cause = match &ecs.fetch::<RunStats>().cause_of_death {
+		Some(cause) => format!("Killed by {}", cause),
+		None => "Killed by unknown forces".to_string(),
+	};
+	ctx.print_color_centered(8, Color::UiText.rgb(), Color::Background.rgb(), &cause);
+	let mut y = run_summary(ecs, ctx, 11) + 1;
+
+	ctx.print_color_centered(y, Color::UiTitle.rgb(), Color::Background.rgb(), "Last messages");
+	y += 1;
+	let log = ecs.fetch::<GameLog>();
+	let recent = log.entries.iter().rev().take(5).collect::<Vec<_>>();
+	for entry in recent.iter().rev() {
+		ctx.print_color_centered(y, Color::UiDim.rgb(), Color::Background.rgb(), entry.to_string());
+		y += 1;
+	}
+	ctx.print_color_centered(y + 1, Color::UiHighlight.rgb(), Color::Background.rgb(), "Press ENTER to return to the menu");
+
+	match ctx.key {
+		Some(VirtualKeyCode::Return | VirtualKeyCode::Escape) => SummaryResult::Dismissed,
+		_ => SummaryResult::NoResponse,
+	}
+}
+
+pub fn highscore_screen(ecs: &World, ctx: &mut rltk::Rltk) -> SummaryResult {
+	tileset::clear_layers(ctx, *ecs.fetch::<GraphicsMode>());
+	ctx.print_color_centered(6, Color::UiTitle.rgb(), Color::Background.rgb(), "High Scores");
+
+	let scores = highscore::load_highscores();
+	if scores.is_empty() {
+		ctx.print_color_centered(9, Color::UiDim.rgb(), Color::Background.rgb(), "No runs recorded yet.");
+	}
+	for (i, entry) in scores.iter().enumerate() {
+		let outcome = if entry.victory {
+			"Escaped with the Amulet".to_string()
+		} else {
+			format!("Killed by {}", entry.cause_of_death.as_deref().unwrap_or("unknown forces"))
+		};
+		let line = format!(
+			"{:>2}. {:>6}  depth {:>2}  {:>5} turns  {:>3} kills  {}",
+			i + 1, entry.score, entry.depth, entry.turns, entry.kills, outcome
+		);
+		let color = if entry.victory { Color::Gold.rgb() } else { Color::UiText.rgb() };
+		ctx.print_color(4, 9 + i as i32, color, Color::Background.rgb(), &line);
+	}
+	ctx.print_color_centered(10 + highscore::MAX_HIGHSCORES as i32 + 1, Color::UiHighlight.rgb(), Color::Background.rgb(), "Press ENTER to return to the menu");
+
+	match ctx.key {
+		Some(VirtualKeyCode::Return | VirtualKeyCode::Escape) => SummaryResult::Dismissed,
+		_ => SummaryResult::NoResponse,
+	}
+}
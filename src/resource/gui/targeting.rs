@@ -1,7 +1,7 @@
-use rltk::{Point, RGB};
+use rltk::Point;
 use specs::WorldExt;
 
-use crate::{component::Viewshed, resource::player::{PlayerData, PlayerEntity}, state::State};
+use crate::{component::Viewshed, resource::{camera::Camera, palette::Color, player::{PlayerData, PlayerEntity}}, state::State};
 
 #[derive(Debug, PartialEq, Clone, Copy)]
 pub enum TargetMenuResult {
@@ -13,15 +13,18 @@ pub fn ranged_target(state: &mut State, ctx: &mut rltk::Rltk, range: i32) -> Tar
 	let player = state.ecs.fetch::<PlayerEntity>().0;
 	let player_data = state.ecs.fetch::<PlayerData>();
 	let viewsheds = state.ecs.read_storage::<Viewshed>();
+	let camera = Camera::new(&state.ecs);
 
-	ctx.print_color(5, 0, RGB::named(rltk::YELLOW), RGB::named(rltk::BLACK), "Select Target:");
+	ctx.print_color(5, 0, Color::UiTitle.rgb(), Color::Background.rgb(), "Select Target:");
 
 	let mut available_cells = Vec::new();
 	if let Some(visible) = viewsheds.get(player) {
 		for idx in visible.visible_tiles.iter() {
 			let distance = rltk::DistanceAlg::Pythagoras.distance2d(player_data.position, *idx);
 			if distance <= range as f32 {
-				ctx.set_bg(idx.x, idx.y, RGB::named(rltk::BLUE));
+				if let Some(screen) = camera.to_screen(*idx) {
+					ctx.set_bg(screen.x, screen.y, Color::UiTargetRange.rgb());
+				}
 				available_cells.push(idx);
 			}
 		}
@@ -30,20 +33,19 @@ pub fn ranged_target(state: &mut State, ctx: &mut rltk::Rltk, range: i32) -> Tar
 	}
 
 	let mouse_pos = ctx.mouse_pos();
-	let valid_target = available_cells
-		.iter()
-		.any(|idx| idx.x == mouse_pos.0 && idx.y == mouse_pos.1);
+	let target = camera.to_world(Point::new(mouse_pos.0, mouse_pos.1));
+	let valid_target = target.is_some_and(|target| available_cells.contains(&&target));
 
 
 	
 
 	if valid_target {
-		ctx.set_bg(mouse_pos.0, mouse_pos.1, RGB::named(rltk::CYAN));
+		ctx.set_bg(mouse_pos.0, mouse_pos.1, Color::UiTargetValid.rgb());
 		if ctx.left_click {
-			return TargetMenuResult::Selected(Point::new(mouse_pos.0, mouse_pos.1));
+			return TargetMenuResult::Selected(target.expect("Valid target outside the map view"));
 		}
 	} else {
-		ctx.set_bg(mouse_pos.0, mouse_pos.1, RGB::named(rltk::RED));
+		ctx.set_bg(mouse_pos.0, mouse_pos.1, Color::UiDanger.rgb());
 		if ctx.left_click {
 			return TargetMenuResult::Cancel;
 		}
@@ -0,0 +1,93 @@
+use rltk::VirtualKeyCode;
+use specs::{Entity, Join, WorldExt};
+
+use crate::{component::{Charges, InBackpack, Name, Purse, Stackable, Value}, resource::{palette::Color, player::PlayerEntity}, state::State};
+
+use super::{item_label, paginate, print_page, turn_page, PAGE_SIZE};
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum VendorMode {
+	Buy,
+	Sell,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum VendorResult {
+	Cancel,
+	NoResponse,
+	ToggleMode,
+	ChangePage(usize),
+	Buy { item: Entity, price: i32 },
+	Sell { item: Entity, price: i32 },
+}
+
+pub fn show_vendor(state: &mut State, ctx: &mut rltk::Rltk, vendor: Entity, mode: VendorMode, page: usize) -> VendorResult {
+	let player_entity = state.ecs.fetch::<PlayerEntity>().0;
+	let names = state.ecs.read_storage::<Name>();
+	let backpack = state.ecs.read_storage::<InBackpack>();
+	let charges = state.ecs.read_storage::<Charges>();
+	let stacks = state.ecs.read_storage::<Stackable>();
+	let values = state.ecs.read_storage::<Value>();
+	let purses = state.ecs.read_storage::<Purse>();
+	let entities = state.ecs.entities();
+
+	let (owner, title) = match mode {
+		VendorMode::Buy => (vendor, format!("Buy from {}", Name::unwrap(names.get(vendor)))),
+		VendorMode::Sell => (player_entity, "Sell which item?".to_string()),
+	};
+	let mut offers = (&entities, &backpack, &names, &values).join()
+		.filter(|item| item.1.owner == owner)
+		.map(|(entity, _pack, name, value)| {
+			let price = match mode {
+				VendorMode::Buy => value.gold,
+				VendorMode::Sell => value.sell_price(),
+			};
+			(entity, item_label(name, stacks.get(entity), charges.get(entity)), price)
+		})
+		.collect::<Vec<_>>();
+	offers.sort_by(|a, b| a.1.cmp(&b.1).then(a.2.cmp(&b.2)));
+	let (page, pages) = paginate(offers.len(), page);
+	let shown = offers.iter().skip(page * PAGE_SIZE).take(PAGE_SIZE).collect::<Vec<_>>();
+	let count = shown.len();
+	let gold = purses.get(player_entity).map(|p| p.gold).unwrap_or_default();
+
+	let y = (25 - (count / 2)) as i32;
+	ctx.draw_box(15, y-2, 51, (count+3) as i32, Color::UiText.rgb(), Color::Background.rgb());
+	ctx.print_color(18, y-2, Color::UiTitle.rgb(), Color::Background.rgb(), &title);
+	let purse = format!("{} gold", gold);
+	ctx.print_color(65 - purse.len() as i32, y-2, Color::Gold.rgb(), Color::Background.rgb(), &purse);
+	ctx.print_color(18, y+count as i32+1, Color::UiTitle.rgb(), Color::Background.rgb(), "TAB to buy/sell, ESCAPE to leave");
+	print_page(ctx, 65, y+count as i32+1, page, pages);
+
+	for (j, (_entity, label, price)) in shown.iter().enumerate() {
+		let y = y + j as i32;
+		ctx.set(17, y, Color::UiText.rgb(), Color::Background.rgb(), rltk::to_cp437('('));
+		ctx.set(18, y, Color::UiTitle.rgb(), Color::Background.rgb(), 97+j as rltk::FontCharType);
+		ctx.set(19, y, Color::UiText.rgb(), Color::Background.rgb(), rltk::to_cp437(')'));
+
+		ctx.print(21, y, label);
+		let price_label = format!("{} gp", price);
+		let price_color = if mode == VendorMode::Buy && *price > gold { Color::UiDanger.rgb() } else { Color::Gold.rgb() };
+		ctx.print_color(65 - price_label.len() as i32, y, price_color, Color::Background.rgb(), &price_label);
+	}
+
+	if let Some(page) = turn_page(ctx.key, page, pages) {
+		return VendorResult::ChangePage(page);
+	}
+	match ctx.key {
+		None => VendorResult::NoResponse,
+		Some(VirtualKeyCode::Escape) => VendorResult::Cancel,
+		Some(VirtualKeyCode::Tab) => VendorResult::ToggleMode,
+		Some(key) => {
+			let selection = rltk::letter_to_option(key);
+			if selection > -1 && selection < count as i32 {
+				let (item, _, price) = *shown[selection as usize];
+				return match mode {
+					VendorMode::Buy => VendorResult::Buy { item, price },
+					VendorMode::Sell => VendorResult::Sell { item, price },
+				};
+			}
+			VendorResult::NoResponse
+		},
+	}
+}
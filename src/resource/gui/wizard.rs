@@ -0,0 +1,164 @@
+use rltk::{Point, VirtualKeyCode};
+
+use crate::{resource::{camera::Camera, map::{Map, TileType}, palette::Color}, state::State, templates::TEMPLATES};
+
+use super::{AmountMenuResult, TargetMenuResult, PAGE_SIZE};
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum WizardAction {
+	RevealMap,
+	GodMode,
+	Teleport,
+	Spawn,
+	Descend,
+	DumpComponents,
+}
+
+const WIZARD_ACTIONS: [(WizardAction, &str); 6] = [
+	(WizardAction::RevealMap, "Reveal the map"),
+	(WizardAction::GodMode, "Toggle god mode"),
+	(WizardAction::Teleport, "Teleport to the cursor"),
+	(WizardAction::Spawn, "Spawn a template at the cursor"),
+	(WizardAction::Descend, "Go to depth..."),
+	(WizardAction::DumpComponents, "Dump component counts"),
+];
+
+/// What happens on the tile picked with the wizard cursor.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum CursorAction {
+	Teleport,
+	Spawn(usize),
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum WizardMenuResult {
+	Cancel,
+	NoResponse,
+	Selected(WizardAction),
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum SpawnMenuResult {
+	Cancel,
+	NoResponse,
+	Selected(usize),
+	ChangePage(usize),
+}
+
+pub fn show_wizard_menu(ctx: &mut rltk::Rltk, god_mode: bool) -> WizardMenuResult {
+	let count = WIZARD_ACTIONS.len();
+	let y = (25 - (count / 2)) as i32;
+	ctx.draw_box(15, y-2, 41, (count+3) as i32, Color::UiText.rgb(), Color::Background.rgb());
+	ctx.print_color(18, y-2, Color::UiHighlight.rgb(), Color::Background.rgb(), "Wizard");
+	ctx.print_color(18, y+count as i32+1, Color::UiTitle.rgb(), Color::Background.rgb(), "ESCAPE to cancel");
+
+	for (j, (action, label)) in WIZARD_ACTIONS.iter().enumerate() {
+		let y = y + j as i32;
+		ctx.set(17, y, Color::UiText.rgb(), Color::Background.rgb(), rltk::to_cp437('('));
+		ctx.set(18, y, Color::UiTitle.rgb(), Color::Background.rgb(), 97+j as rltk::FontCharType);
+		ctx.set(19, y, Color::UiText.rgb(), Color::Background.rgb(), rltk::to_cp437(')'));
+
+		ctx.print(21, y, label);
+		if *action == WizardAction::GodMode {
+			let status = if god_mode { "on" } else { "off" };
+			ctx.print_color(55 - status.len() as i32, y, Color::UiHighlight.rgb(), Color::Background.rgb(), status);
+		}
+	}
+
+	match ctx.key {
+		None => WizardMenuResult::NoResponse,
+		Some(VirtualKeyCode::Escape) => WizardMenuResult::Cancel,
+		Some(key) => {
+			let selection = rltk::letter_to_option(key);
+			if selection > -1 && selection < count as i32 {
+				return WizardMenuResult::Selected(WIZARD_ACTIONS[selection as usize].0);
+			}
+			WizardMenuResult::NoResponse
+		},
+	}
+}
+
+pub fn show_spawn_menu(ctx: &mut rltk::Rltk, page: usize) -> SpawnMenuResult {
+	let pages = TEMPLATES.len().div_ceil(PAGE_SIZE);
+	let page = usize::min(page, pages - 1);
+	let shown = TEMPLATES.iter().enumerate().skip(page * PAGE_SIZE).take(PAGE_SIZE).collect::<Vec<_>>();
+	let count = shown.len();
+
+	let y = (25 - (count / 2)) as i32;
+	ctx.draw_box(15, y-2, 31, (count+3) as i32, Color::UiText.rgb(), Color::Background.rgb());
+	ctx.print_color(18, y-2, Color::UiHighlight.rgb(), Color::Background.rgb(), "Spawn what?");
+	ctx.print_color(18, y+count as i32+1, Color::UiTitle.rgb(), Color::Background.rgb(), "ESCAPE to cancel");
+	if pages > 1 {
+		let page_label = format!("<{}/{}>", page + 1, pages);
+		ctx.print_color(45 - page_label.len() as i32, y+count as i32+1, Color::UiTitle.rgb(), Color::Background.rgb(), &page_label);
+	}
+
+	for (j, (_index, (name, _spawner))) in shown.iter().enumerate() {
+		let y = y + j as i32;
+		ctx.set(17, y, Color::UiText.rgb(), Color::Background.rgb(), rltk::to_cp437('('));
+		ctx.set(18, y, Color::UiTitle.rgb(), Color::Background.rgb(), 97+j as rltk::FontCharType);
+		ctx.set(19, y, Color::UiText.rgb(), Color::Background.rgb(), rltk::to_cp437(')'));
+
+		ctx.print(21, y, name);
+	}
+
+	match ctx.key {
+		None => SpawnMenuResult::NoResponse,
+		Some(VirtualKeyCode::Escape) => SpawnMenuResult::Cancel,
+		Some(VirtualKeyCode::PageDown | VirtualKeyCode::Right) if page + 1 < pages => SpawnMenuResult::ChangePage(page + 1),
+		Some(VirtualKeyCode::PageUp | VirtualKeyCode::Left) if page > 0 => SpawnMenuResult::ChangePage(page - 1),
+		Some(key) => {
+			let selection = rltk::letter_to_option(key);
+			if selection > -1 && selection < count as i32 {
+				return SpawnMenuResult::Selected(shown[selection as usize].0);
+			}
+			SpawnMenuResult::NoResponse
+		},
+	}
+}
+
+pub fn pick_depth(ctx: &mut rltk::Rltk, depth: i32, max_depth: i32) -> AmountMenuResult {
+	let depth = depth.clamp(1, max_depth);
+	ctx.draw_box(15, 22, 31, 4, Color::UiText.rgb(), Color::Background.rgb());
+	ctx.print_color(18, 22, Color::UiHighlight.rgb(), Color::Background.rgb(), "Go to which depth?");
+	let counter = format!("< {} / {} >", depth, max_depth);
+	ctx.print_color(31 - counter.len() as i32 / 2, 24, Color::UiText.rgb(), Color::Background.rgb(), &counter);
+	ctx.print_color(18, 26, Color::UiTitle.rgb(), Color::Background.rgb(), "ENTER to go");
+
+	match ctx.key {
+		Some(VirtualKeyCode::Escape) => AmountMenuResult::Cancel,
+		Some(VirtualKeyCode::Return) => AmountMenuResult::Selected { amount: depth },
+		Some(VirtualKeyCode::Left | VirtualKeyCode::Down) => AmountMenuResult::NoResponse { amount: i32::max(1, depth - 1) },
+		Some(VirtualKeyCode::Right | VirtualKeyCode::Up) => AmountMenuResult::NoResponse { amount: i32::min(max_depth, depth + 1) },
+		_ => AmountMenuResult::NoResponse { amount: depth },
+	}
+}
+
+/// Lets the wizard click any floor tile of the map, seen or not.
+pub fn wizard_cursor(state: &mut State, ctx: &mut rltk::Rltk, action: CursorAction) -> TargetMenuResult {
+	let map = state.ecs.fetch::<Map>();
+	let prompt = match action {
+		CursorAction::Teleport => "Teleport where?".to_string(),
+		CursorAction::Spawn(index) => format!("Spawn {} where?", TEMPLATES[index].0),
+	};
+	ctx.print_color(5, 0, Color::UiHighlight.rgb(), Color::Background.rgb(), &prompt);
+
+	let (x, y) = ctx.mouse_pos();
+	let target = Camera::new(&state.ecs).to_world(Point::new(x, y))
+		.filter(|target| map.in_bounds(target.x, target.y) && map.tiles[map.xy_idx(target.x, target.y)] != TileType::Wall);
+	if let Some(target) = target {
+		ctx.set_bg(x, y, Color::UiTargetValid.rgb());
+		if ctx.left_click {
+			return TargetMenuResult::Selected(target);
+		}
+	} else {
+		ctx.set_bg(x, y, Color::UiDanger.rgb());
+		if ctx.left_click {
+			return TargetMenuResult::Cancel;
+		}
+	}
+	if ctx.key == Some(VirtualKeyCode::Escape) {
+		return TargetMenuResult::Cancel;
+	}
+	TargetMenuResult::NoResponse
+}
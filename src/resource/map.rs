@@ -1,14 +1,26 @@
 use std::cmp::{max, min};
 
-use rltk::{Point, RandomNumberGenerator, Rltk, Tile, RGB};
+use rltk::{Point, RandomNumberGenerator, Rltk, Tile};
 use serde::{Deserialize, Serialize};
 use specs::{Entity, World};
 
 use crate::utils::rect::Rect;
 
-pub const WIDTH: i32 = 80;
-pub const HEIGHT: i32 = 43;
-pub const SIZE: usize = (WIDTH * HEIGHT) as usize;
+use super::{
+    camera::{Camera, VIEW_HEIGHT, VIEW_WIDTH},
+    palette::Color,
+    prefab,
+    random_table::Spawner,
+    tileset::{self, GraphicsMode},
+};
+
+pub const FINAL_DEPTH: i32 = 10;
+/// Level sizes the generator picks from, from cramped warrens to halls far bigger than the screen.
+pub const LEVEL_SIZES: [(i32, i32); 4] = [(50, 30), (80, 43), (120, 70), (200, 120)];
+/// Size of the final level, which fits on one screen.
+pub const ARENA_SIZE: (i32, i32) = (80, 43);
+/// Orthogonal neighbours in `wall_masks` bit order: north 1, east 2, south 4, west 8.
+const NEIGHBOURS: [(i32, i32); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
 
 #[derive(PartialEq, Copy, Clone, Serialize, Deserialize)]
 pub enum TileType {
@@ -31,19 +43,38 @@ pub struct Map {
     #[serde(skip_serializing)]
     #[serde(skip_deserializing)]
     pub tile_content: Vec<Vec<Entity>>,
+
+    /// Which orthogonal neighbours of each tile are walls, in `NEIGHBOURS` bit order.
+    #[serde(skip_serializing)]
+    #[serde(skip_deserializing)]
+    pub wall_masks: Vec<u8>,
+
+    /// Entities drawn into prefabs, spawned along with the rest of the level.
+    #[serde(skip_serializing)]
+    #[serde(skip_deserializing)]
+    pub prefab_spawns: Vec<(i32, i32, Spawner)>,
+
+    /// Rooms taken up by a prefab, which get no random spawns.
+    #[serde(skip_serializing)]
+    #[serde(skip_deserializing)]
+    pub vault_rooms: Vec<usize>,
 }
 
 impl Map {
-    fn new(depth: i32) -> Self {
+    pub fn new(width: i32, height: i32, depth: i32) -> Self {
+        let size = (width * height) as usize;
         Self {
-            tiles: vec![TileType::Wall; SIZE],
+            tiles: vec![TileType::Wall; size],
             rooms: Vec::new(),
-            width: WIDTH,
-            height: HEIGHT,
-            revealed_tiles: vec![false; SIZE],
-            visible_tiles: vec![false; SIZE],
-            blocked: vec![false; SIZE],
-            tile_content: vec![Vec::new(); SIZE],
+            width,
+            height,
+            revealed_tiles: vec![false; size],
+            visible_tiles: vec![false; size],
+            blocked: vec![false; size],
+            tile_content: vec![Vec::new(); size],
+            wall_masks: vec![0; size],
+            prefab_spawns: Vec::new(),
+            vault_rooms: Vec::new(),
             depth,
         }
     }
@@ -59,18 +90,18 @@ impl Map {
 
     fn add_horizontal_tunnel(&mut self, x1: i32, x2: i32, y: i32) {
         for x in min(x1, x2)..=max(x1, x2) {
-            let idx = self.xy_idx(x, y);
-            if idx > 0 && idx < SIZE {
-                self.tiles[idx as usize] = TileType::Floor;
+            if self.in_bounds(x, y) {
+                let idx = self.xy_idx(x, y);
+                self.tiles[idx] = TileType::Floor;
             }
         }
     }
 
     fn add_vertical_tunnel(&mut self, y1: i32, y2: i32, x: i32) {
         for y in min(y1, y2)..=max(y1, y2) {
-            let idx = self.xy_idx(x, y);
-            if idx > 0 && idx < SIZE {
-                self.tiles[idx as usize] = TileType::Floor;
+            if self.in_bounds(x, y) {
+                let idx = self.xy_idx(x, y);
+                self.tiles[idx] = TileType::Floor;
             }
         }
     }
@@ -95,6 +126,37 @@ impl Map {
         }
     }
 
+    /// Recomputes every tile's wall neighbours; call whenever the terrain changes.
+    /// Tiles beyond the edge of the map count as walls.
+    pub fn refresh_wall_masks(&mut self) {
+        let is_wall = |x: i32, y: i32| !self.in_bounds(x, y) || self.tiles[self.xy_idx(x, y)] == TileType::Wall;
+        let masks = (0..self.height)
+            .flat_map(|y| (0..self.width).map(move |x| (x, y)))
+            .map(|(x, y)| {
+                NEIGHBOURS
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, (dx, dy))| is_wall(x + dx, y + dy))
+                    .fold(0, |mask, (bit, _)| mask | 1 << bit)
+            })
+            .collect();
+        self.wall_masks = masks;
+    }
+
+    /// The wall neighbours of a tile that the player has already seen.
+    pub fn revealed_wall_mask(&self, x: i32, y: i32) -> u8 {
+        let seen = NEIGHBOURS
+            .iter()
+            .enumerate()
+            .filter(|(_, (dx, dy))| self.in_bounds(x + dx, y + dy) && self.revealed_tiles[self.xy_idx(x + dx, y + dy)])
+            .fold(0, |mask, (bit, _)| mask | 1 << bit);
+        self.wall_masks[self.xy_idx(x, y)] & seen
+    }
+
+    pub fn in_bounds(&self, x: i32, y: i32) -> bool {
+        x >= 0 && x < self.width && y >= 0 && y < self.height
+    }
+
     pub fn xy_idx(&self, x: i32, y: i32) -> usize {
         (y * self.width) as usize + x as usize
     }
@@ -154,19 +216,33 @@ impl rltk::BaseMap for Map {
     }
 }
 
-pub fn generate_map(depth: i32) -> Map {
-    let mut map = Map::new(depth);
-    const MAX_ROOMS: i32 = 30;
+pub fn generate_map(depth: i32, rng: &mut RandomNumberGenerator) -> Map {
+    let mut map = generate_layout(depth, rng);
+    map.refresh_wall_masks();
+    map
+}
+
+fn generate_layout(depth: i32, rng: &mut RandomNumberGenerator) -> Map {
+    if depth >= FINAL_DEPTH {
+        return generate_boss_arena(depth);
+    }
+    if let Some(level) = prefab::level_prefab(depth) {
+        return prefab::build_level(level, depth);
+    }
+
+    let (width, height) = LEVEL_SIZES[rng.range(0, LEVEL_SIZES.len())];
+    let mut map = Map::new(width, height, depth);
+    const ROOMS_PER_SCREEN: i32 = 30;
     const MIN_SIZE: i32 = 6;
     const MAX_SIZE: i32 = 10;
+    let (screen_width, screen_height) = LEVEL_SIZES[1];
+    let max_rooms = ROOMS_PER_SCREEN * width * height / (screen_width * screen_height);
 
-    let mut rng = RandomNumberGenerator::new();
-
-    for _ in 0..MAX_ROOMS {
+    for _ in 0..max_rooms {
         let w = rng.range(MIN_SIZE, MAX_SIZE);
         let h = rng.range(MIN_SIZE, MAX_SIZE);
-        let x = rng.roll_dice(1, WIDTH - w - 1) - 1;
-        let y = rng.roll_dice(1, HEIGHT - h - 1) - 1;
+        let x = rng.roll_dice(1, width - w - 1) - 1;
+        let y = rng.roll_dice(1, height - h - 1) - 1;
         let new_room = Rect::new(x, y, w, h);
 
         let intersects = map.rooms.iter().any(|other| other.intersect(&new_room));
@@ -188,6 +264,8 @@ pub fn generate_map(depth: i32) -> Map {
         }
     }
 
+    prefab::stamp_rooms(&mut map, rng);
+
     let stairs_position = map.rooms[map.rooms.len() - 1].center();
     let stairs_idx = map.xy_idx(stairs_position.0, stairs_position.1);
     map.tiles[stairs_idx] = TileType::DownStairs;
@@ -195,25 +273,88 @@ pub fn generate_map(depth: i32) -> Map {
     map
 }
 
+/// The final level: a small antechamber leading into a pillared hall, with no way further down.
+fn generate_boss_arena(depth: i32) -> Map {
+    let (width, height) = ARENA_SIZE;
+    let mut map = Map::new(width, height, depth);
+
+    let entry = Rect::new(3, height / 2 - 4, 8, 8);
+    let arena = Rect::new(22, 3, width - 26, height - 7);
+    map.add_room(&entry);
+    map.add_room(&arena);
+    let (entry_x, entry_y) = entry.center();
+    let (arena_x, _) = arena.center();
+    map.add_horizontal_tunnel(entry_x, arena_x, entry_y);
+
+    for y in (arena.y1 + 4..arena.y2 - 2).step_by(6) {
+        for x in (arena.x1 + 5..arena.x2 - 3).step_by(10) {
+            for (dx, dy) in [(0, 0), (1, 0), (0, 1), (1, 1)] {
+                if y + dy == entry_y {
+                    continue;
+                }
+                let idx = map.xy_idx(x + dx, y + dy);
+                map.tiles[idx] = TileType::Wall;
+            }
+        }
+    }
+
+    map.rooms.push(entry);
+    map.rooms.push(arena);
+    map
+}
+
+/// CP437 box-drawing character joining a wall to its neighbours in `mask`.
+fn wall_glyph(mask: u8) -> rltk::FontCharType {
+    match mask {
+        1 | 4 | 5 => 186,
+        2 | 8 | 10 => 205,
+        3 => 200,
+        6 => 201,
+        12 => 187,
+        9 => 188,
+        7 => 204,
+        14 => 203,
+        13 => 185,
+        11 => 202,
+        15 => 206,
+        _ => 9,
+    }
+}
+
 pub fn draw_map(ecs: &World, ctx: &mut Rltk) {
     let map = ecs.fetch::<Map>();
+    let camera = Camera::new(ecs);
+    let mode = *ecs.fetch::<GraphicsMode>();
+    tileset::use_layer(ctx, mode, tileset::MAP_LAYER);
 
-    for (idx, tile) in map.tiles.iter().enumerate() {
-        let x = idx % WIDTH as usize;
-        let y = idx / WIDTH as usize;
-
+    for (screen_x, screen_y) in (0..VIEW_HEIGHT).flat_map(|y| (0..VIEW_WIDTH).map(move |x| (x, y))) {
+        let Some(world) = camera.to_world(Point::new(screen_x, screen_y)) else {
+            continue;
+        };
+        if !map.in_bounds(world.x, world.y) {
+            continue;
+        }
+        let idx = map.xy_idx(world.x, world.y);
         if !map.revealed_tiles[idx] {
             continue;
         }
 
-        let (glyph, mut fg) = match tile {
-            TileType::Floor => (rltk::to_cp437('.'), RGB::from_f32(0.0, 0.5, 0.5)),
-            TileType::Wall => (rltk::to_cp437('#'), RGB::from_f32(0.0, 1.0, 0.0)),
-            TileType::DownStairs => (rltk::to_cp437('>'), RGB::from_f32(0.0, 1.0, 1.0)),
-        };
-        if !map.visible_tiles[idx] {
-            fg = fg.to_greyscale();
+        let visible = map.visible_tiles[idx];
+        if mode == GraphicsMode::Tiles {
+            let tint = if visible { Color::SpriteVisible } else { Color::SpriteRemembered };
+            let sprite = tileset::tile_sprite(&map, idx);
+            ctx.set(screen_x, screen_y, tint.rgb(), Color::Background.rgb(), sprite);
+            continue;
         }
-        ctx.set(x, y, fg, RGB::from_f32(0.0, 0.0, 0.0), glyph);
+
+        let (glyph, fg) = match map.tiles[idx] {
+            TileType::Floor => (rltk::to_cp437('.'), if visible { Color::FloorVisible } else { Color::FloorRemembered }),
+            TileType::Wall => (
+                wall_glyph(map.revealed_wall_mask(world.x, world.y)),
+                if visible { Color::WallVisible } else { Color::WallRemembered },
+            ),
+            TileType::DownStairs => (rltk::to_cp437('>'), if visible { Color::StairsVisible } else { Color::StairsRemembered }),
+        };
+        ctx.set(screen_x, screen_y, fg.rgb(), Color::Background.rgb(), glyph);
     }
 }
@@ -1,15 +1,28 @@
 use specs::World;
 
+pub mod camera;
 pub mod gamelog;
 pub mod gui;
 pub mod map;
+pub mod overlay;
+pub mod palette;
 pub mod player;
+pub mod prefab;
 pub mod random_table;
+pub mod run_stats;
+pub mod settings;
 pub mod spawner;
+pub mod tileset;
+pub mod wizard;
 
-pub fn insert_resources(ecs: &mut World) {
-    ecs.insert(map::generate_map(1));
+/// Every random roll of a game comes from one generator seeded with `seed`, so a seed
+/// and the player's inputs are enough to reproduce the game.
+pub fn insert_resources(ecs: &mut World, seed: u64, settings: &settings::Settings) {
+    let mut rng = rltk::RandomNumberGenerator::seeded(seed);
+    ecs.insert(map::generate_map(1, &mut rng));
     ecs.insert(player::PlayerData::new(0, 0));
-    ecs.insert(gamelog::GameLog::new());
-    ecs.insert(rltk::RandomNumberGenerator::new());
+    ecs.insert(gamelog::GameLog::new(settings.log_length));
+    ecs.insert(rng);
+    ecs.insert(run_stats::RunStats::new());
+    ecs.insert(overlay::DebugOverlays::default());
 }
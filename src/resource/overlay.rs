@@ -0,0 +1,33 @@
+use std::collections::HashMap;
+
+use specs::Entity;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Overlay {
+	Blocked,
+	TileContent,
+	Viewsheds,
+	Paths,
+	Rooms,
+}
+
+/// Debug views drawn over the map, plus the data the systems leave behind for them.
+#[derive(Default)]
+pub struct DebugOverlays {
+	pub active: Vec<Overlay>,
+	pub monster_paths: HashMap<Entity, Vec<usize>>,
+}
+
+impl DebugOverlays {
+	pub fn is_active(&self, overlay: Overlay) -> bool {
+		self.active.contains(&overlay)
+	}
+
+	pub fn toggle(&mut self, overlay: Overlay) {
+		if self.is_active(overlay) {
+			self.active.retain(|active| *active != overlay);
+		} else {
+			self.active.push(overlay);
+		}
+	}
+}
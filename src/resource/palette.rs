@@ -0,0 +1,274 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use rltk::RGB;
+use serde::{Deserialize, Serialize};
+
+/// What a colour means rather than what it looks like. Entities and the GUI ask for one
+/// of these and the active palette decides the actual RGB value.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+pub enum Color {
+    Background,
+    Player,
+    Vendor,
+    Hostile,
+    HostileWeak,
+    HostileUndead,
+    HostileVenomous,
+    HostileBrute,
+    Boss,
+    Corpse,
+    Healing,
+    Magic,
+    Fire,
+    Confusion,
+    Weapon,
+    Armor,
+    Gold,
+    QuestItem,
+    FloorVisible,
+    FloorRemembered,
+    WallVisible,
+    WallRemembered,
+    StairsVisible,
+    StairsRemembered,
+    /// Tint of tileset sprites, which carry their own colours.
+    SpriteVisible,
+    SpriteRemembered,
+    UiText,
+    UiDim,
+    UiTitle,
+    UiHighlight,
+    /// Background of tooltips and other floating panels.
+    UiPanel,
+    UiDanger,
+    UiTargetRange,
+    UiTargetValid,
+    /// Hit points lost and gained, as shown in the message log.
+    Damage,
+    Heal,
+}
+
+impl Color {
+    pub fn rgb(self) -> RGB {
+        RGB::named(active().colors(self))
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
+pub enum PaletteName {
+    Classic,
+    Deuteranopia,
+    Protanopia,
+    HighContrast,
+}
+
+pub const PALETTES: [PaletteName; 4] = [
+    PaletteName::Classic,
+    PaletteName::Deuteranopia,
+    PaletteName::Protanopia,
+    PaletteName::HighContrast,
+];
+
+impl PaletteName {
+    pub fn label(self) -> &'static str {
+        match self {
+            PaletteName::Classic => "classic",
+            PaletteName::Deuteranopia => "deuteranopia",
+            PaletteName::Protanopia => "protanopia",
+            PaletteName::HighContrast => "high contrast",
+        }
+    }
+
+    pub fn colors(self, color: Color) -> (u8, u8, u8) {
+        match self {
+            PaletteName::Classic => classic(color),
+            PaletteName::Deuteranopia => deuteranopia(color),
+            PaletteName::Protanopia => protanopia(color),
+            PaletteName::HighContrast => high_contrast(color),
+        }
+    }
+}
+
+/// Index into `PALETTES` of the palette in use. Drawing code all over the GUI needs it
+/// and none of it should care which palette is picked, so it lives outside the ECS.
+static ACTIVE: AtomicUsize = AtomicUsize::new(0);
+
+pub fn active() -> PaletteName {
+    PALETTES[ACTIVE.load(Ordering::Relaxed)]
+}
+
+pub fn set_palette(name: PaletteName) {
+    let idx = PALETTES.iter().position(|palette| *palette == name).unwrap_or(0);
+    ACTIVE.store(idx, Ordering::Relaxed);
+}
+
+/// The colours the game always had.
+fn classic(color: Color) -> (u8, u8, u8) {
+    match color {
+        Color::Background => rltk::BLACK,
+        Color::Player => rltk::YELLOW,
+        Color::Vendor => rltk::CYAN,
+        Color::Hostile => rltk::RED,
+        Color::HostileWeak => rltk::BROWN1,
+        Color::HostileUndead => rltk::WHITE_SMOKE,
+        Color::HostileVenomous => rltk::PURPLE,
+        Color::HostileBrute => rltk::SANDY_BROWN,
+        Color::Boss => rltk::CRIMSON,
+        Color::Corpse => rltk::DARK_RED,
+        Color::Healing => rltk::MAGENTA,
+        Color::Magic => rltk::CYAN,
+        Color::Fire => rltk::ORANGE,
+        Color::Confusion => rltk::PINK,
+        Color::Weapon => rltk::LIGHT_GRAY,
+        Color::Armor => rltk::CYAN,
+        Color::Gold => rltk::GOLD,
+        Color::QuestItem => rltk::GOLD,
+        Color::FloorVisible => (0, 127, 127),
+        Color::FloorRemembered => (89, 89, 89),
+        Color::WallVisible => (0, 255, 0),
+        Color::WallRemembered => (150, 150, 150),
+        Color::StairsVisible => (0, 255, 255),
+        Color::StairsRemembered => (179, 179, 179),
+        Color::SpriteVisible => rltk::WHITE,
+        Color::SpriteRemembered => (127, 127, 127),
+        Color::UiText => rltk::WHITE,
+        Color::UiDim => rltk::GRAY,
+        Color::UiTitle => rltk::YELLOW,
+        Color::UiHighlight => rltk::MAGENTA,
+        Color::UiPanel => rltk::GRAY10,
+        Color::UiDanger => rltk::RED,
+        Color::UiTargetRange => rltk::BLUE,
+        Color::UiTargetValid => rltk::CYAN,
+        Color::Damage => rltk::RED,
+        Color::Heal => rltk::GREEN,
+    }
+}
+
+/// Built on the Okabe-Ito set: nothing relies on telling red from green, danger is
+/// vermillion and orange, and friendly or magical things are blue.
+fn deuteranopia(color: Color) -> (u8, u8, u8) {
+    match color {
+        Color::Background => (0, 0, 0),
+        Color::Player => (240, 228, 66),
+        Color::Vendor => (86, 180, 233),
+        Color::Hostile => (213, 94, 0),
+        Color::HostileWeak => (230, 159, 0),
+        Color::HostileUndead => (240, 240, 240),
+        Color::HostileVenomous => (204, 121, 167),
+        Color::HostileBrute => (255, 176, 120),
+        Color::Boss => (255, 70, 0),
+        Color::Corpse => (140, 70, 20),
+        Color::Healing => (230, 140, 200),
+        Color::Magic => (86, 180, 233),
+        Color::Fire => (230, 159, 0),
+        Color::Confusion => (204, 121, 167),
+        Color::Weapon => (200, 200, 200),
+        Color::Armor => (110, 160, 255),
+        Color::Gold => (240, 200, 40),
+        Color::QuestItem => (240, 228, 66),
+        Color::FloorVisible => (50, 110, 170),
+        Color::FloorRemembered => (70, 70, 70),
+        Color::WallVisible => (86, 180, 233),
+        Color::WallRemembered => (130, 130, 130),
+        Color::StairsVisible => (240, 228, 66),
+        Color::StairsRemembered => (170, 170, 170),
+        Color::SpriteVisible => (255, 255, 255),
+        Color::SpriteRemembered => (127, 127, 127),
+        Color::UiText => (255, 255, 255),
+        Color::UiDim => (150, 150, 150),
+        Color::UiTitle => (240, 228, 66),
+        Color::UiHighlight => (86, 180, 233),
+        Color::UiPanel => (30, 30, 40),
+        Color::UiDanger => (255, 110, 0),
+        Color::UiTargetRange => (0, 90, 150),
+        Color::UiTargetValid => (86, 180, 233),
+        Color::Damage => (255, 110, 0),
+        Color::Heal => (86, 180, 233),
+    }
+}
+
+/// Reds look dark to protanopes, so danger is drawn in bright oranges and yellows
+/// and everything on the player's side in blues.
+fn protanopia(color: Color) -> (u8, u8, u8) {
+    match color {
+        Color::Background => (0, 0, 0),
+        Color::Player => (130, 210, 255),
+        Color::Vendor => (0, 140, 255),
+        Color::Hostile => (255, 150, 0),
+        Color::HostileWeak => (220, 200, 90),
+        Color::HostileUndead => (240, 240, 240),
+        Color::HostileVenomous => (200, 140, 255),
+        Color::HostileBrute => (255, 200, 140),
+        Color::Boss => (255, 230, 0),
+        Color::Corpse => (150, 110, 60),
+        Color::Healing => (200, 140, 255),
+        Color::Magic => (0, 190, 255),
+        Color::Fire => (255, 180, 0),
+        Color::Confusion => (220, 170, 255),
+        Color::Weapon => (200, 200, 200),
+        Color::Armor => (120, 170, 255),
+        Color::Gold => (255, 220, 60),
+        Color::QuestItem => (255, 255, 120),
+        Color::FloorVisible => (60, 100, 160),
+        Color::FloorRemembered => (70, 70, 70),
+        Color::WallVisible => (150, 190, 230),
+        Color::WallRemembered => (120, 120, 120),
+        Color::StairsVisible => (255, 255, 120),
+        Color::StairsRemembered => (170, 170, 170),
+        Color::SpriteVisible => (255, 255, 255),
+        Color::SpriteRemembered => (127, 127, 127),
+        Color::UiText => (255, 255, 255),
+        Color::UiDim => (150, 150, 150),
+        Color::UiTitle => (255, 220, 60),
+        Color::UiHighlight => (0, 190, 255),
+        Color::UiPanel => (30, 30, 40),
+        Color::UiDanger => (255, 150, 0),
+        Color::UiTargetRange => (0, 70, 160),
+        Color::UiTargetValid => (0, 190, 255),
+        Color::Damage => (255, 150, 0),
+        Color::Heal => (0, 190, 255),
+    }
+}
+
+/// Everything at full brightness, and remembered tiles dim enough to never be mistaken
+/// for what is in view.
+fn high_contrast(color: Color) -> (u8, u8, u8) {
+    match color {
+        Color::Background => (0, 0, 0),
+        Color::Player => (255, 255, 255),
+        Color::Vendor => (0, 255, 255),
+        Color::Hostile => (255, 40, 40),
+        Color::HostileWeak => (255, 140, 40),
+        Color::HostileUndead => (255, 255, 255),
+        Color::HostileVenomous => (255, 0, 255),
+        Color::HostileBrute => (255, 90, 0),
+        Color::Boss => (255, 0, 0),
+        Color::Corpse => (160, 60, 60),
+        Color::Healing => (255, 0, 255),
+        Color::Magic => (0, 255, 255),
+        Color::Fire => (255, 160, 0),
+        Color::Confusion => (255, 150, 255),
+        Color::Weapon => (255, 255, 255),
+        Color::Armor => (0, 200, 255),
+        Color::Gold => (255, 255, 0),
+        Color::QuestItem => (255, 255, 0),
+        Color::FloorVisible => (170, 170, 170),
+        Color::FloorRemembered => (50, 50, 50),
+        Color::WallVisible => (255, 255, 255),
+        Color::WallRemembered => (90, 90, 90),
+        Color::StairsVisible => (255, 255, 0),
+        Color::StairsRemembered => (110, 110, 0),
+        Color::SpriteVisible => (255, 255, 255),
+        Color::SpriteRemembered => (90, 90, 90),
+        Color::UiText => (255, 255, 255),
+        Color::UiDim => (170, 170, 170),
+        Color::UiTitle => (255, 255, 0),
+        Color::UiHighlight => (0, 255, 255),
+        Color::UiPanel => (0, 0, 90),
+        Color::UiDanger => (255, 40, 40),
+        Color::UiTargetRange => (0, 0, 200),
+        Color::UiTargetValid => (0, 255, 0),
+        Color::Damage => (255, 40, 40),
+        Color::Heal => (0, 255, 0),
+    }
+}
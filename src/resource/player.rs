@@ -2,14 +2,30 @@ use rltk::Point;
 use specs::Entity;
 
 pub struct PlayerData {
-	pub position: Point
+	pub position: Point,
+	/// Where the player is walking to after clicking on the map, if anywhere.
+	pub walk: Option<AutoWalk>,
 }
 
 impl PlayerData {
 	pub fn new(x: i32, y: i32) -> Self {
-		Self { position: Point::new(x, y) }
+		Self { position: Point::new(x, y), walk: None }
 	}
 }
 
+/// A destination the player walks towards one step per turn.
+#[derive(Clone)]
+pub struct AutoWalk {
+	pub target: Point,
+	/// Monsters in view on the last step; the walk stops as soon as a new one shows up.
+	pub monsters_in_view: Vec<Entity>,
+	/// Hit points on the last step; the walk stops if they drop.
+	pub hp: i32,
+	/// Steps taken so far, which replays use to cancel the walk at the same point.
+	pub steps: u32,
+	/// Item to pick up on arrival, for walks started from an item's menu.
+	pub pickup: Option<Entity>,
+}
+
 
 pub struct PlayerEntity(pub Entity);
@@ -0,0 +1,171 @@
+use rltk::{rex::XpFile, Point, RandomNumberGenerator};
+
+use crate::{templates, utils::rect::Rect};
+
+use super::{
+    map::{Map, TileType},
+    random_table::Spawner,
+};
+
+/// A hand-drawn room or vault that is stamped into a generated level.
+pub struct RoomPrefab {
+    pub data: &'static [u8],
+    pub min_depth: i32,
+    pub max_depth: i32,
+    /// One in `frequency` levels within the depth range gets this room.
+    pub frequency: i32,
+}
+
+/// A hand-drawn level that replaces the generator at one depth.
+pub struct LevelPrefab {
+    pub data: &'static [u8],
+    pub depth: i32,
+}
+
+pub const ROOM_PREFABS: [RoomPrefab; 4] = [
+    RoomPrefab {
+        data: include_bytes!("../../resources/prefabs/shrine.xp"),
+        min_depth: 1,
+        max_depth: 9,
+        frequency: 4,
+    },
+    RoomPrefab {
+        data: include_bytes!("../../resources/prefabs/goblin_camp.xp"),
+        min_depth: 1,
+        max_depth: 5,
+        frequency: 3,
+    },
+    RoomPrefab {
+        data: include_bytes!("../../resources/prefabs/vault.xp"),
+        min_depth: 2,
+        max_depth: 9,
+        frequency: 3,
+    },
+    RoomPrefab {
+        data: include_bytes!("../../resources/prefabs/ogre_den.xp"),
+        min_depth: 8,
+        max_depth: 9,
+        frequency: 3,
+    },
+];
+
+pub const LEVEL_PREFABS: [LevelPrefab; 1] = [LevelPrefab {
+    data: include_bytes!("../../resources/prefabs/crypt.xp"),
+    depth: 5,
+}];
+
+/// What a prefab glyph turns into: the tile underneath and whatever stands on it.
+/// Glyphs outside the legend, and transparent cells, leave the generated tile alone.
+fn legend(glyph: char) -> Option<(TileType, Option<Spawner>)> {
+    use templates::*;
+    let spawner: Spawner = match glyph {
+        '#' => return Some((TileType::Wall, None)),
+        '.' | '@' => return Some((TileType::Floor, None)),
+        '>' => return Some((TileType::DownStairs, None)),
+        'r' => rat,
+        'k' => kobold,
+        'g' => goblin,
+        'o' => orc,
+        's' => skeleton,
+        'S' => spider,
+        'T' => troll,
+        'O' => ogre,
+        '!' => health_potion,
+        '?' => magic_missile_scroll,
+        '/' => magic_missile_wand,
+        '$' => gold_pile,
+        ')' => dagger,
+        '|' => longsword,
+        '[' => shield,
+        _ => return None,
+    };
+    Some((TileType::Floor, Some(spawner)))
+}
+
+fn load(data: &'static [u8]) -> XpFile {
+    XpFile::read(&mut &data[..]).expect("Unable to read prefab")
+}
+
+/// Copies every layer of `prefab` onto the map with its top left corner at `x`, `y`,
+/// queueing its entities for the spawner. Returns where the `@` start marker was, if any.
+fn stamp(map: &mut Map, prefab: &XpFile, x: i32, y: i32) -> Option<Point> {
+    let mut start = None;
+    for layer in prefab.layers.iter() {
+        for py in 0..layer.height {
+            for px in 0..layer.width {
+                let Some(cell) = layer.get(px, py) else {
+                    continue;
+                };
+                if cell.bg.is_transparent() {
+                    continue;
+                }
+                let glyph = rltk::to_char(cell.ch as u8);
+                let Some((tile, spawner)) = legend(glyph) else {
+                    continue;
+                };
+                let (tx, ty) = (x + px as i32, y + py as i32);
+                if !map.in_bounds(tx, ty) {
+                    continue;
+                }
+                let idx = map.xy_idx(tx, ty);
+                map.tiles[idx] = tile;
+                if let Some(spawner) = spawner {
+                    map.prefab_spawns.push((tx, ty, spawner));
+                }
+                if glyph == '@' {
+                    start = Some(Point::new(tx, ty));
+                }
+            }
+        }
+    }
+    start
+}
+
+pub fn level_prefab(depth: i32) -> Option<&'static LevelPrefab> {
+    LEVEL_PREFABS.iter().find(|prefab| prefab.depth == depth)
+}
+
+/// Builds a whole level from a prefab. Its only room is the `@` the player arrives on.
+pub fn build_level(prefab: &LevelPrefab, depth: i32) -> Map {
+    let prefab = load(prefab.data);
+    let (width, height) = (prefab.layers[0].width as i32, prefab.layers[0].height as i32);
+    let mut map = Map::new(width, height, depth);
+    let start = stamp(&mut map, &prefab, 0, 0).expect("Level prefab without a start");
+    map.rooms.push(Rect::new(start.x - 1, start.y - 1, 2, 2));
+    map
+}
+
+/// Rolls for every room prefab allowed at this depth and stamps the winners into rooms
+/// with at least a tile of floor to spare on each side, so corridors crossing the room
+/// can still get around it. The arrival and stairs rooms are left alone.
+pub fn stamp_rooms(map: &mut Map, rng: &mut RandomNumberGenerator) {
+    for room_prefab in ROOM_PREFABS.iter() {
+        if map.depth < room_prefab.min_depth || map.depth > room_prefab.max_depth {
+            continue;
+        }
+        if rng.roll_dice(1, room_prefab.frequency) != 1 {
+            continue;
+        }
+
+        let prefab = load(room_prefab.data);
+        let (width, height) = (prefab.layers[0].width as i32, prefab.layers[0].height as i32);
+        let last_room = map.rooms.len().saturating_sub(1);
+        let candidates = (1..last_room)
+            .filter(|i| !map.vault_rooms.contains(i))
+            .filter(|i| {
+                let room = map.rooms[*i];
+                room.x2 - room.x1 >= width + 2 && room.y2 - room.y1 >= height + 2
+            })
+            .collect::<Vec<_>>();
+        if candidates.is_empty() {
+            continue;
+        }
+
+        let chosen = candidates[rng.range(0, candidates.len())];
+        let room = map.rooms[chosen];
+        let x = room.x1 + 1 + (room.x2 - room.x1 - width) / 2;
+        let y = room.y1 + 1 + (room.y2 - room.y1 - height) / 2;
+        stamp(map, &prefab, x, y);
+        map.vault_rooms.push(chosen);
+    }
+}
@@ -23,6 +23,9 @@ impl RandomTable {
     }
 
     pub fn add(mut self, spawner: Spawner, weight: i32) -> Self {
+        if weight <= 0 {
+            return self;
+        }
         self.entries.push(RandomEntry { spawner, weight });
         self.total_weight += weight;
         self
@@ -0,0 +1,27 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// Running tally of the current game, shown on the victory and game over screens.
+#[derive(Default, Clone, Serialize, Deserialize)]
+pub struct RunStats {
+    pub turns: i32,
+    pub kills: BTreeMap<String, i32>,
+    pub items_used: i32,
+    pub victory: bool,
+    pub cause_of_death: Option<String>,
+}
+
+impl RunStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record_kill(&mut self, name: &str) {
+        *self.kills.entry(name.to_string()).or_insert(0) += 1;
+    }
+
+    pub fn total_kills(&self) -> i32 {
+        self.kills.values().sum()
+    }
+}
@@ -0,0 +1,201 @@
+use std::{env, fs, path::PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use super::{palette::{PaletteName, PALETTES}, tileset::GraphicsMode};
+
+const SETTINGS_FILE: &str = "settings.json";
+const TILE_SIZES: [u32; 5] = [8, 12, 16, 20, 24];
+const LOG_LENGTHS: [usize; 4] = [50, 100, 250, 1000];
+
+#[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
+pub enum FontChoice {
+    Terminal8x8,
+    Vga8x16,
+}
+
+impl FontChoice {
+    pub fn file(self) -> &'static str {
+        match self {
+            FontChoice::Terminal8x8 => "terminal8x8.png",
+            FontChoice::Vga8x16 => "vga8x16.png",
+        }
+    }
+
+    pub fn glyph_size(self) -> (u32, u32) {
+        match self {
+            FontChoice::Terminal8x8 => (8, 8),
+            FontChoice::Vga8x16 => (8, 16),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum SettingsOption {
+    Fullscreen,
+    Scanlines,
+    TileSize,
+    ShowMouse,
+    Font,
+    Graphics,
+    Palette,
+    LogLength,
+    Autosave,
+}
+
+pub const OPTIONS: [SettingsOption; 9] = [
+    SettingsOption::Fullscreen,
+    SettingsOption::Scanlines,
+    SettingsOption::TileSize,
+    SettingsOption::ShowMouse,
+    SettingsOption::Font,
+    SettingsOption::Graphics,
+    SettingsOption::Palette,
+    SettingsOption::LogLength,
+    SettingsOption::Autosave,
+];
+
+impl SettingsOption {
+    pub fn name(self) -> &'static str {
+        match self {
+            SettingsOption::Fullscreen => "Fullscreen",
+            SettingsOption::Scanlines => "Scanlines",
+            SettingsOption::TileSize => "Tile size",
+            SettingsOption::ShowMouse => "Mouse cursor",
+            SettingsOption::Font => "Font",
+            SettingsOption::Graphics => "Graphics",
+            SettingsOption::Palette => "Colour palette",
+            SettingsOption::LogLength => "Message log length",
+            SettingsOption::Autosave => "Autosave on new level",
+        }
+    }
+
+    /// Options baked into the window when it is created, which only change on the next start.
+    pub fn needs_restart(self) -> bool {
+        matches!(
+            self,
+            SettingsOption::Fullscreen | SettingsOption::TileSize | SettingsOption::Font | SettingsOption::Graphics
+        )
+    }
+}
+
+/// Player preferences, kept in `settings.json` in the user's config directory.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub fullscreen: bool,
+    pub scanlines: bool,
+    pub tile_size: u32,
+    pub show_mouse: bool,
+    pub font: FontChoice,
+    pub graphics: GraphicsMode,
+    pub palette: PaletteName,
+    pub log_length: usize,
+    pub autosave: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            fullscreen: true,
+            scanlines: true,
+            tile_size: 16,
+            show_mouse: false,
+            font: FontChoice::Terminal8x8,
+            graphics: GraphicsMode::Ascii,
+            palette: PaletteName::Classic,
+            log_length: 100,
+            autosave: false,
+        }
+    }
+}
+
+/// Next or previous entry of `values` after `current`, wrapping around.
+fn cycle<T: PartialEq + Copy>(values: &[T], current: T, forward: bool) -> T {
+    let idx = values.iter().position(|v| *v == current).unwrap_or(0);
+    let next = if forward { idx + 1 } else { idx + values.len() - 1 };
+    values[next % values.len()]
+}
+
+impl Settings {
+    /// Pixel size of one console cell; the VGA font is twice as tall as it is wide.
+    pub fn tile_dimensions(&self) -> (u32, u32) {
+        let (width, height) = self.font.glyph_size();
+        (self.tile_size, self.tile_size * height / width)
+    }
+
+    pub fn value(&self, option: SettingsOption) -> String {
+        let on_off = |on: bool| if on { "on" } else { "off" }.to_string();
+        match option {
+            SettingsOption::Fullscreen => on_off(self.fullscreen),
+            SettingsOption::Scanlines => on_off(self.scanlines),
+            SettingsOption::TileSize => format!("{}px", self.tile_size),
+            SettingsOption::ShowMouse => on_off(self.show_mouse),
+            SettingsOption::Font => self.font.file().trim_end_matches(".png").to_string(),
+            SettingsOption::Graphics => format!("{:?}", self.graphics).to_lowercase(),
+            SettingsOption::Palette => self.palette.label().to_string(),
+            SettingsOption::LogLength => format!("{} messages", self.log_length),
+            SettingsOption::Autosave => on_off(self.autosave),
+        }
+    }
+
+    pub fn change(&mut self, option: SettingsOption, forward: bool) {
+        match option {
+            SettingsOption::Fullscreen => self.fullscreen = !self.fullscreen,
+            SettingsOption::Scanlines => self.scanlines = !self.scanlines,
+            SettingsOption::TileSize => self.tile_size = cycle(&TILE_SIZES, self.tile_size, forward),
+            SettingsOption::ShowMouse => self.show_mouse = !self.show_mouse,
+            SettingsOption::Font => {
+                self.font = cycle(&[FontChoice::Terminal8x8, FontChoice::Vga8x16], self.font, forward)
+            }
+            SettingsOption::Graphics => {
+                self.graphics = cycle(&[GraphicsMode::Ascii, GraphicsMode::Tiles], self.graphics, forward)
+            }
+            SettingsOption::Palette => self.palette = cycle(&PALETTES, self.palette, forward),
+            SettingsOption::LogLength => self.log_length = cycle(&LOG_LENGTHS, self.log_length, forward),
+            SettingsOption::Autosave => self.autosave = !self.autosave,
+        }
+    }
+
+    /// Reads the settings file, falling back to the defaults if there is none or it is unreadable.
+    pub fn load() -> Self {
+        settings_path()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|data| serde_json::from_str(&data).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) {
+        let Some(path) = settings_path() else {
+            return;
+        };
+        if let Some(dir) = path.parent() {
+            if fs::create_dir_all(dir).is_err() {
+                return;
+            }
+        }
+        if let Ok(data) = serde_json::to_string_pretty(self) {
+            let _ = fs::write(path, data);
+        }
+    }
+}
+
+/// `$XDG_CONFIG_HOME`, `%APPDATA%` or the platform's usual spot under the home directory.
+fn config_dir() -> Option<PathBuf> {
+    if let Some(dir) = env::var_os("XDG_CONFIG_HOME").filter(|dir| !dir.is_empty()) {
+        return Some(dir.into());
+    }
+    if cfg!(windows) {
+        return env::var_os("APPDATA").map(PathBuf::from);
+    }
+    let home = PathBuf::from(env::var_os("HOME")?);
+    if cfg!(target_os = "macos") {
+        Some(home.join("Library").join("Application Support"))
+    } else {
+        Some(home.join(".config"))
+    }
+}
+
+fn settings_path() -> Option<PathBuf> {
+    Some(config_dir()?.join("rogue-tutorial").join(SETTINGS_FILE))
+}
@@ -1,11 +1,102 @@
 use rltk::RandomNumberGenerator;
-use specs::{World, WorldExt};
+use specs::{Entity, Join, World, WorldExt};
 
-use crate::{templates, utils::rect::Rect};
+use crate::{component::{InBackpack, Item, Loot, Position}, templates, utils::rect::Rect};
 
-use super::random_table::RandomTable;
+use super::{
+    map::{self, Map},
+    random_table::RandomTable,
+};
 
 pub const MAX_SPAWNS: i32 = 4;
+pub const SHOP_CHANCE: i32 = 3;
+pub const SHOP_STOCK: i32 = 6;
+
+/// Populates every room but the first, which is where the player arrives, and brings the
+/// level's prefabs to life.
+pub fn spawn_level(ecs: &mut World, map: &Map) {
+    let (rooms, depth) = (&map.rooms, map.depth);
+    for (x, y, spawner) in map.prefab_spawns.iter() {
+        spawner(ecs, *x, *y);
+    }
+    if depth >= map::FINAL_DEPTH {
+        spawn_boss_arena(ecs, rooms);
+        return;
+    }
+
+    let shop_room = {
+        let mut rng = ecs.write_resource::<RandomNumberGenerator>();
+        if rooms.len() > 2 && rng.roll_dice(1, SHOP_CHANCE) == 1 {
+            Some(rng.range(1, rooms.len() - 1))
+        } else {
+            None
+        }
+    };
+
+    for (i, room) in rooms.iter().enumerate().skip(1) {
+        if map.vault_rooms.contains(&i) {
+            continue;
+        }
+        if Some(i) == shop_room {
+            spawn_shop(ecs, room, depth);
+        } else {
+            spawn_room(ecs, room, depth);
+        }
+    }
+}
+
+/// The dragon waits in the middle of the hall, flanked by two skeleton guards. Nothing
+/// here scales with depth. A player with the longsword and shield (power 9, defense 3,
+/// 30 hp) cuts down both guards in two turns for about 3 hp, then needs about nine turns
+/// and 36 hp for the dragon, plus 12 hp for every fire breath taken while closing in.
+/// The arena as a whole takes two or three health potions, or scrolls to shorten the
+/// dragon fight.
+fn spawn_boss_arena(ecs: &mut World, rooms: &[Rect]) {
+    let Some(arena) = rooms.get(1) else {
+        return;
+    };
+    let (x, y) = arena.center();
+    templates::dragon(ecs, x, y);
+    for dx in [-3, 3] {
+        templates::skeleton_guard(ecs, x + dx, y);
+    }
+}
+
+pub fn spawn_shop(ecs: &mut World, room: &Rect, depth: i32) {
+    let (x, y) = room.center();
+    let vendor = templates::shopkeeper(ecs, x, y);
+    let stock_table = shop_table(depth);
+    for _ in 0..SHOP_STOCK {
+        let roll = {
+            let mut rng = ecs.write_resource::<RandomNumberGenerator>();
+            stock_table.roll(&mut rng)
+        };
+        if let Some(entry) = roll {
+            (entry.spawner)(ecs, x, y);
+        }
+    }
+    stock_vendor(ecs, vendor, x, y);
+}
+
+/// Moves the items lying under the vendor into its stock.
+fn stock_vendor(ecs: &mut World, vendor: Entity, x: i32, y: i32) {
+    let mut positions = ecs.write_storage::<Position>();
+    let mut backpack = ecs.write_storage::<InBackpack>();
+    let items = ecs.read_storage::<Item>();
+    let entities = ecs.entities();
+
+    let stock = (&entities, &items, &positions)
+        .join()
+        .filter(|(_, _, pos)| pos.x == x && pos.y == y)
+        .map(|(item, ..)| item)
+        .collect::<Vec<_>>();
+    for item in stock {
+        positions.remove(item);
+        backpack
+            .insert(item, InBackpack::new(vendor))
+            .expect("Unable to stock vendor");
+    }
+}
 
 pub fn spawn_room(ecs: &mut World, room: &Rect, depth: i32) {
     let spawn_table = room_table(depth);
@@ -51,13 +142,82 @@ fn select_points(
     points
 }
 
+/// Spawn weight that is only present between `min` and `max` depth, inclusive.
+fn in_depth(depth: i32, min: i32, max: i32, weight: i32) -> i32 {
+    if depth >= min && depth <= max {
+        weight
+    } else {
+        0
+    }
+}
+
 fn room_table(depth: i32) -> RandomTable {
     use templates::*;
     RandomTable::new()
-        .add(goblin, 10 + depth)
-        .add(orc, 1 + depth)
+        .add(rat, in_depth(depth, 1, 3, 8))
+        .add(kobold, in_depth(depth, 1, 5, 6))
+        .add(goblin, in_depth(depth, 1, 7, 10))
+        .add(orc, in_depth(depth, 2, 10, 1 + depth))
+        .add(skeleton, in_depth(depth, 3, 12, depth))
+        .add(spider, in_depth(depth, 4, 12, depth - 1))
+        .add(troll, in_depth(depth, 6, i32::MAX, depth - 4))
+        .add(ogre, in_depth(depth, 8, i32::MAX, depth - 6))
         .add(health_potion, 7)
         .add(fireball_scroll, 2)
         .add(confusion_scroll, 2)
         .add(magic_missile_scroll, 4)
+        .add(magic_missile_wand, depth)
+        .add(confusion_wand, depth - 1)
+        .add(fireball_staff, depth - 2)
+        .add(recharge_scroll, depth - 1)
+        .add(gold_pile, 5)
+        .add(dagger, 3)
+        .add(shield, 3)
+        .add(longsword, depth - 1)
+}
+
+fn shop_table(depth: i32) -> RandomTable {
+    use templates::*;
+    RandomTable::new()
+        .add(health_potion, 10)
+        .add(magic_missile_scroll, 5)
+        .add(confusion_scroll, 3)
+        .add(fireball_scroll, 2 + depth)
+        .add(magic_missile_wand, 1 + depth)
+        .add(recharge_scroll, depth)
+        .add(dagger, 3)
+        .add(shield, 3)
+        .add(longsword, depth)
+}
+
+pub fn loot_table(table: Loot, depth: i32) -> RandomTable {
+    use templates::*;
+    match table {
+        Loot::Goblin => RandomTable::new()
+            .add(health_potion, 4)
+            .add(dagger, 2)
+            .add(magic_missile_scroll, 2)
+            .add(confusion_scroll, 1),
+        Loot::Orc => RandomTable::new()
+            .add(health_potion, 4)
+            .add(fireball_scroll, 1 + depth)
+            .add(confusion_scroll, 2)
+            .add(magic_missile_wand, depth),
+        Loot::Kobold => RandomTable::new()
+            .add(dagger, 3)
+            .add(health_potion, 2),
+        Loot::Skeleton => RandomTable::new()
+            .add(longsword, 1)
+            .add(shield, 2),
+        Loot::Spider => RandomTable::new().add(health_potion, 1),
+        Loot::Troll => RandomTable::new()
+            .add(health_potion, 3)
+            .add(fireball_staff, 1)
+            .add(recharge_scroll, 2),
+        Loot::Ogre => RandomTable::new()
+            .add(longsword, 2)
+            .add(confusion_wand, 1)
+            .add(health_potion, 3),
+        Loot::Boss => RandomTable::new().add(amulet, 1),
+    }
 }
@@ -0,0 +1,57 @@
+use rltk::{FontCharType, Rltk};
+use serde::{Deserialize, Serialize};
+
+use super::map::{Map, TileType};
+
+/// How the game is drawn: CP437 glyphs, or sprites from `resources/tiles.png`.
+#[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
+pub enum GraphicsMode {
+    Ascii,
+    Tiles,
+}
+
+pub const TILE_FONT: &str = "tiles.png";
+
+/// Consoles of the tile mode, bottom to top. Text and anything without a sprite goes on
+/// the last one, which is also the only console in ASCII mode.
+pub const MAP_LAYER: usize = 0;
+pub const SPRITE_LAYER: usize = 1;
+pub const TEXT_LAYER: usize = 2;
+
+const FLOOR_SPRITE: FontCharType = 0;
+const PLAYER_SPRITE: FontCharType = 2;
+const STAIRS_SPRITE: FontCharType = 3;
+/// First of sixteen wall sprites, one for every `Map::wall_masks` value.
+const WALL_SPRITES: FontCharType = 16;
+
+pub fn tile_sprite(map: &Map, idx: usize) -> FontCharType {
+    match map.tiles[idx] {
+        TileType::Floor => FLOOR_SPRITE,
+        TileType::DownStairs => STAIRS_SPRITE,
+        TileType::Wall => WALL_SPRITES + map.wall_masks[idx] as FontCharType,
+    }
+}
+
+/// Sprite standing in for an entity's glyph, for the few entities the sheet has art for.
+pub fn entity_sprite(glyph: FontCharType) -> Option<FontCharType> {
+    (glyph == rltk::to_cp437('@')).then_some(PLAYER_SPRITE)
+}
+
+/// Sends the following draw calls to `layer`. ASCII mode draws everything on its one console.
+pub fn use_layer(ctx: &mut Rltk, mode: GraphicsMode, layer: usize) {
+    if mode == GraphicsMode::Tiles {
+        ctx.set_active_console(layer);
+    }
+}
+
+/// Clears every console, leaving the text layer active.
+pub fn clear_layers(ctx: &mut Rltk, mode: GraphicsMode) {
+    if mode == GraphicsMode::Tiles {
+        for layer in [MAP_LAYER, SPRITE_LAYER, TEXT_LAYER] {
+            ctx.set_active_console(layer);
+            ctx.cls();
+        }
+    } else {
+        ctx.cls();
+    }
+}
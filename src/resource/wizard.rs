@@ -0,0 +1,12 @@
+/// Cheats available when the game is started with `--wizard`.
+#[derive(Default, Clone, Copy)]
+pub struct WizardMode {
+	pub enabled: bool,
+	pub god_mode: bool,
+}
+
+impl WizardMode {
+	pub fn new(enabled: bool) -> Self {
+		Self { enabled, god_mode: false }
+	}
+}
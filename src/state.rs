@@ -1,39 +1,113 @@
-use rltk::Point;
+use rltk::{Point, RandomNumberGenerator};
 use specs::prelude::*;
 use specs::storage::GenericReadStorage;
 use specs::storage::GenericWriteStorage;
 
 use crate::component::*;
+use crate::resource::camera::Camera;
 use crate::resource::gamelog::GameLog;
 use crate::resource::gui;
 use crate::resource::gui::show_inventory;
+use crate::resource::gui::AmountMenuResult;
+use crate::resource::gui::ContextMenuResult;
+use crate::resource::gui::CursorAction;
+use crate::resource::gui::ItemAction;
+use crate::resource::gui::InventoryView;
 use crate::resource::gui::ItemMenuResult;
+use crate::resource::gui::LookResult;
 use crate::resource::gui::MainMenuResult;
 use crate::resource::gui::MainMenuSelection;
+use crate::resource::gui::MessageLogResult;
+use crate::resource::gui::OptionsResult;
+use crate::resource::gui::SpawnMenuResult;
+use crate::resource::gui::SummaryResult;
 use crate::resource::gui::TargetMenuResult;
+use crate::resource::gui::VendorMode;
+use crate::resource::gui::VendorResult;
+use crate::resource::gui::WizardAction;
+use crate::resource::gui::WizardMenuResult;
 use crate::resource::insert_resources;
 use crate::resource::map::*;
+use crate::resource::palette;
+use crate::resource::palette::Color;
 use crate::resource::player::PlayerData;
 use crate::resource::player::PlayerEntity;
+use crate::resource::run_stats::RunStats;
+use crate::resource::settings::Settings;
+use crate::resource::settings::OPTIONS;
 use crate::resource::spawner;
+use crate::resource::tileset;
+use crate::resource::tileset::GraphicsMode;
+use crate::resource::wizard::WizardMode;
 use crate::system;
 use crate::system::damage;
+use crate::system::player;
 use crate::system::player::player_input;
+use crate::system::highscore;
+use crate::system::item_pickup::MAX_BURDEN;
+use crate::system::morgue;
+use crate::system::replay;
+use crate::system::replay::Playback;
+use crate::system::replay::Replay;
 use crate::system::saveload_system;
 use crate::templates;
 
+/// How far the player can throw an item.
+const THROW_RANGE: i32 = 6;
+
 #[derive(PartialEq, Clone, Copy)]
 pub enum RunState {
     AwaitingInput,
     PreRun,
     PlayerTurn,
     MonsterTurn,
-    ShowInventory,
-    ShowDropItem,
+    AutoWalk,
+    ShowInventory { view: InventoryView },
+    ShowDropItem { view: InventoryView },
+    ItemActions { item: Entity, selection: usize, view: InventoryView },
+    ExamineItem { item: Entity, view: InventoryView },
+    ShowDropAmount { item: Entity, amount: i32 },
+    ShowVendor { vendor: Entity, mode: VendorMode, page: usize },
     ShowTargeting { range: i32, item: Entity },
+    ShowThrowTargeting { item: Entity },
+    ShowMessageLog { offset: usize },
+    LookCursor { cursor: Point },
+    ItemContext { item: Entity, selection: usize },
+    ShowCharacter,
     MainMenu { menu_selection: MainMenuSelection },
     SaveGame,
     NextLevel,
+    Victory,
+    GameOver,
+    ShowHighScores,
+    ShowOptions { selection: usize },
+    ShowWizardMenu,
+    ShowWizardSpawn { page: usize },
+    ShowWizardDepth { depth: i32 },
+    WizardCursor { action: CursorAction },
+}
+
+impl RunState {
+    /// States that wait on the player, or that input can interrupt, whose input is recorded for replays.
+    pub fn takes_input(&self) -> bool {
+        matches!(
+            self,
+            RunState::AwaitingInput
+                | RunState::ShowInventory { .. }
+                | RunState::ShowDropItem { .. }
+                | RunState::ItemActions { .. }
+                | RunState::ExamineItem { .. }
+                | RunState::ShowDropAmount { .. }
+                | RunState::ShowVendor { .. }
+                | RunState::ShowTargeting { .. }
+                | RunState::ShowThrowTargeting { .. }
+                | RunState::ShowMessageLog { .. }
+                | RunState::LookCursor { .. }
+                | RunState::ItemContext { .. }
+                | RunState::ShowCharacter
+                | RunState::AutoWalk
+        )
+    }
 }
 
 impl Default for RunState {
@@ -46,39 +120,86 @@ impl Default for RunState {
 
 pub struct State {
     pub ecs: World,
+    pub playback: Option<Playback>,
+    pub wizard: bool,
+    pub graphics: GraphicsMode,
+    pub settings: Settings,
+    /// Whether the right mouse button went down this frame.
+    pub right_click: bool,
+    right_button_down: bool,
 }
 
 impl State {
     pub fn new() -> Self {
-        Self { ecs: World::new() }
+        Self {
+            ecs: World::new(),
+            playback: None,
+            wizard: false,
+            graphics: GraphicsMode::Ascii,
+            settings: Settings::default(),
+            right_click: false,
+            right_button_down: false,
+        }
     }
 
     pub fn setup(&mut self) {
+        let seed = RandomNumberGenerator::new().next_u64();
+        self.new_game(seed);
+        self.start_recording(seed, None);
+    }
+
+    /// Picks up the saved game where it was left, recording from there on.
+    pub fn resume(&mut self) {
+        let seed = RandomNumberGenerator::new().next_u64();
+        let save = saveload_system::read_save();
+        self.resume_game(seed, &save);
+        self.start_recording(seed, Some(save));
+    }
+
+    fn start_recording(&mut self, seed: u64, save: Option<String>) {
+        if !self.wizard {
+            self.ecs.insert(Replay::new(seed, self.settings.autosave, save));
+        }
+    }
+
+    /// Replaces the world with a fresh first level rolled from `seed`. The world is rebuilt
+    /// rather than cleared so entity ids, and with them system order, repeat for a seed.
+    pub fn new_game(&mut self, seed: u64) {
+        self.ecs = World::new();
         self.ecs.insert(RunState::default());
         register_components(&mut self.ecs);
-        insert_resources(&mut self.ecs);
+        insert_resources(&mut self.ecs, seed, &self.settings);
+        self.ecs.insert(WizardMode::new(self.wizard));
+        self.ecs.insert(self.graphics);
+        self.ecs.insert(Replay::disabled());
 
-        let map = self.ecs.fetch::<Map>();
-        let rooms = map.rooms.clone();
-        let depth = map.depth;
-        drop(map);
-        let (player_x, player_y) = rooms[0].center();
-        for room in rooms.iter().skip(1) {
-            spawner::spawn_room(&mut self.ecs, room, depth);
-        }
+        let map = Map::clone(&self.ecs.fetch::<Map>());
+        let (player_x, player_y) = map.rooms[0].center();
+        spawner::spawn_level(&mut self.ecs, &map);
 
         let player = templates::create_player(&mut self.ecs, player_x, player_y);
         self.ecs.insert(PlayerEntity(player));
+        self.ecs.insert(PlayerData::new(player_x, player_y));
+    }
+
+    /// Applies the settings that can change while the window is open.
+    pub fn apply_settings(&mut self, ctx: &mut rltk::Rltk) {
+        ctx.with_post_scanlines(self.settings.scanlines);
+        ctx.with_mouse_visibility(self.settings.show_mouse);
+        palette::set_palette(self.settings.palette);
+        self.ecs.fetch_mut::<GameLog>().limit = self.settings.log_length;
     }
 
     pub fn render(&mut self, ctx: &mut rltk::Rltk) {
-        ctx.cls();
+        let mode = *self.ecs.fetch::<GraphicsMode>();
+        tileset::clear_layers(ctx, mode);
 
         draw_map(&self.ecs, ctx);
 
         let positions = self.ecs.read_storage::<Position>();
         let renderables = self.ecs.read_storage::<Renderable>();
         let map = self.ecs.fetch::<Map>();
+        let camera = Camera::new(&self.ecs);
 
         let mut data = (&positions, &renderables).join().collect::<Vec<_>>();
         data.sort_by(|&a, &b| b.1.render_order.cmp(&a.1.render_order));
@@ -88,13 +209,42 @@ impl State {
             if !map.visible_tiles[idx] {
                 continue;
             }
-            ctx.set(pos.x, pos.y, render.fg, render.bg, render.glyph);
+            let Some(screen) = camera.to_screen(Point::new(pos.x, pos.y)) else {
+                continue;
+            };
+            match tileset::entity_sprite(render.glyph).filter(|_| mode == GraphicsMode::Tiles) {
+                Some(sprite) => {
+                    tileset::use_layer(ctx, mode, tileset::SPRITE_LAYER);
+                    ctx.set(screen.x, screen.y, Color::SpriteVisible.rgb(), render.bg.rgb(), sprite);
+                }
+                None => {
+                    tileset::use_layer(ctx, mode, tileset::TEXT_LAYER);
+                    ctx.set(screen.x, screen.y, render.fg.rgb(), render.bg.rgb(), render.glyph);
+                }
+            }
         }
+        drop(map);
+        tileset::use_layer(ctx, mode, tileset::TEXT_LAYER);
+        gui::draw_overlays(&self.ecs, ctx);
 
         ctx.print(1, 1, "Hello Rogue");
     }
 
     fn goto_next_level(&mut self) {
+        let current_depth = self.ecs.fetch::<Map>().depth;
+        self.goto_level(current_depth + 1);
+
+        let player_entity = self.ecs.fetch::<PlayerEntity>().0;
+        let mut gamelog = self.ecs.fetch_mut::<GameLog>();
+        gamelog.log("You descend to the next level, and take a moment to heal.".to_owned());
+        let mut combat_stats = self.ecs.write_storage::<CombatStats>();
+        if let Some(health) = combat_stats.get_mut(player_entity) {
+            health.hp = i32::max(health.hp, health.max_hp / 2);
+        }
+    }
+
+    /// Swaps the current level for a freshly generated one at `depth`, keeping the player and their gear.
+    fn goto_level(&mut self, depth: i32) {
         let to_delete = self.entities_to_remove_on_level_change();
         self.ecs
             .delete_entities(&to_delete)
@@ -102,14 +252,12 @@ impl State {
 
         let worldmap = {
             let mut worldmap_resource = self.ecs.write_resource::<Map>();
-            let current_depth = worldmap_resource.depth;
-            *worldmap_resource = generate_map(current_depth + 1);
+            let mut rng = self.ecs.write_resource::<RandomNumberGenerator>();
+            *worldmap_resource = generate_map(depth, &mut rng);
             worldmap_resource.clone()
         };
 
-        for room in worldmap.rooms.iter().skip(1) {
-            spawner::spawn_room(&mut self.ecs, room, worldmap.depth);
-        }
+        spawner::spawn_level(&mut self.ecs, &worldmap);
         let (player_x, player_y) = worldmap.rooms[0].center();
         let mut player_data = self.ecs.write_resource::<PlayerData>();
         player_data.position = Point::new(player_x, player_y);
@@ -124,19 +272,334 @@ impl State {
         if let Some(viewshed) = viewshed_components.get_mut(player_entity) {
             viewshed.dirty = true;
         }
+    }
 
-        let mut gamelog = self.ecs.fetch_mut::<GameLog>();
-        gamelog.log("You descend to the next level, and take a moment to heal.".to_owned());
-        let mut combat_stats = self.ecs.write_storage::<CombatStats>();
-        if let Some(health) = combat_stats.get_mut(player_entity) {
-            health.hp = i32::max(health.hp, health.max_hp / 2);
+    fn wizard_action(&mut self, action: WizardAction) -> RunState {
+        match action {
+            WizardAction::RevealMap => {
+                let mut map = self.ecs.fetch_mut::<Map>();
+                map.revealed_tiles.iter_mut().for_each(|tile| *tile = true);
+                RunState::AwaitingInput
+            }
+            WizardAction::GodMode => {
+                let mut wizard = self.ecs.fetch_mut::<WizardMode>();
+                wizard.god_mode = !wizard.god_mode;
+                let status = if wizard.god_mode { "on" } else { "off" };
+                self.ecs
+                    .fetch_mut::<GameLog>()
+                    .log(format!("God mode is {}.", status));
+                RunState::AwaitingInput
+            }
+            WizardAction::Teleport => RunState::WizardCursor {
+                action: CursorAction::Teleport,
+            },
+            WizardAction::Spawn => RunState::ShowWizardSpawn { page: 0 },
+            WizardAction::Descend => RunState::ShowWizardDepth {
+                depth: self.ecs.fetch::<Map>().depth,
+            },
+            WizardAction::DumpComponents => {
+                let counts = component_counts(&self.ecs);
+                for (component, count) in counts.iter() {
+                    rltk::console::log(format!("{}: {}", component, count));
+                }
+                let entity_count = self.ecs.entities().join().count();
+                self.ecs.fetch_mut::<GameLog>().log(format!(
+                    "{} entities, {} component types; counts dumped to the console.",
+                    entity_count,
+                    counts.len()
+                ));
+                RunState::AwaitingInput
+            }
         }
     }
 
+    fn wizard_cursor_action(&mut self, action: CursorAction, target: Point) {
+        match action {
+            CursorAction::Teleport => {
+                let player_entity = self.ecs.fetch::<PlayerEntity>().0;
+                if let Some(pos) = self.ecs.write_storage::<Position>().get_mut(player_entity) {
+                    pos.x = target.x;
+                    pos.y = target.y;
+                }
+                if let Some(viewshed) = self.ecs.write_storage::<Viewshed>().get_mut(player_entity) {
+                    viewshed.dirty = true;
+                }
+                self.ecs.write_resource::<PlayerData>().position = target;
+            }
+            CursorAction::Spawn(index) => {
+                let (name, spawner) = templates::TEMPLATES[index];
+                spawner(&mut self.ecs, target.x, target.y);
+                self.ecs
+                    .fetch_mut::<GameLog>()
+                    .log(format!("You conjure a {}.", name));
+            }
+        }
+    }
+
+    /// Restores `save` over a fresh world rolled from `seed`. Loading on top of the same
+    /// world every time keeps entity ids in step between a resumed game and its replay.
+    fn resume_game(&mut self, seed: u64, save: &str) {
+        self.new_game(seed);
+        saveload_system::load_game(&mut self.ecs, save);
+        let turn = self.ecs.fetch::<RunStats>().turns;
+        self.ecs.fetch_mut::<GameLog>().turn = turn;
+    }
+
+    /// Starts the recorded game over and lets the replay drive it.
+    pub fn start_playback(&mut self, replay: Replay) {
+        match &replay.save {
+            Some(save) => {
+                self.resume_game(replay.seed, save);
+                self.ecs.insert(RunState::AwaitingInput);
+            }
+            None => {
+                self.new_game(replay.seed);
+                self.ecs.insert(RunState::PreRun);
+            }
+        }
+        self.playback = Some(Playback::new(replay));
+    }
+
+    /// Leaves a morgue file, a high score entry and a replay behind for a finished run.
+    /// A run that was itself a playback is checked against the recording instead.
+    fn end_run(&mut self) {
+        if let Some(playback) = self.playback.take() {
+            let message = match playback.replay.final_hash {
+                Some(hash) if hash == replay::state_hash(&self.ecs) => {
+                    "Replay finished: the final state matches the recording."
+                }
+                Some(_) => "Replay finished: the final state DIFFERS from the recording!",
+                None => "Replay finished.",
+            };
+            rltk::console::log(message);
+            self.ecs.fetch_mut::<GameLog>().log(message.to_string());
+            return;
+        }
+        if self.settings.autosave {
+            saveload_system::delete_save();
+        }
+        morgue::write_morgue(&self.ecs);
+        if !self.ecs.fetch::<WizardMode>().enabled {
+            highscore::record_highscore(&self.ecs);
+        }
+        self.ecs.write_resource::<Replay>().finish(&self.ecs);
+    }
+
+    /// What the player carries and their carry capacity.
+    fn player_burden(&self) -> Option<(f32, f32)> {
+        let player = self.ecs.fetch::<PlayerEntity>().0;
+        let capacity = self.ecs.read_storage::<CombatStats>().get(player)?.carry_capacity();
+        let carried = Weight::carried_by(
+            player,
+            &self.ecs.entities(),
+            &self.ecs.read_storage::<InBackpack>(),
+            &self.ecs.read_storage::<Equipped>(),
+            &self.ecs.read_storage::<Weight>(),
+            &self.ecs.read_storage::<Stackable>(),
+        );
+        Some((carried, capacity))
+    }
+
+    fn player_overburdened(&self) -> bool {
+        self.player_burden().is_some_and(|(carried, capacity)| carried > capacity)
+    }
+
+    /// Whether one of `item` still fits in the player's pack, by the same limit that
+    /// `ItemCollectionSystem` puts on picking things up.
+    fn player_can_carry(&self, item: Entity) -> bool {
+        let weight = self.ecs.read_storage::<Weight>().get(item).map(|w| w.weight).unwrap_or(0.0);
+        self.player_burden().is_none_or(|(carried, capacity)| carried + weight <= capacity * MAX_BURDEN)
+    }
+
+    fn trade(&mut self, item: Entity, price: i32, seller: Entity, buyer: Entity) {
+        let player = self.ecs.fetch::<PlayerEntity>().0;
+        let item_name = Name::unwrap(self.ecs.read_storage::<Name>().get(item)).to_string();
+        if buyer == player && !self.player_can_carry(item) {
+            self.ecs
+                .fetch_mut::<GameLog>()
+                .log(format!("The {} is too heavy, you cannot carry any more.", item_name));
+            return;
+        }
+        {
+            let mut purses = self.ecs.write_storage::<Purse>();
+            let mut gamelog = self.ecs.fetch_mut::<GameLog>();
+            let funds = purses.get(buyer).map(|p| p.gold).unwrap_or_default();
+            if funds < price {
+                if buyer == player {
+                    gamelog.log(format!("You cannot afford the {}.", item_name));
+                } else {
+                    gamelog.log("The shopkeeper cannot afford that.".to_string());
+                }
+                return;
+            }
+            if let Some(purse) = purses.get_mut(buyer) {
+                purse.gold -= price;
+            }
+            if let Some(purse) = purses.get_mut(seller) {
+                purse.gold += price;
+            }
+            if buyer == player {
+                gamelog.log(format!("You buy the {} for {} gold.", item_name, price));
+            } else {
+                gamelog.log(format!("You sell the {} for {} gold.", item_name, price));
+            }
+        }
+
+        let quantity = self.ecs.read_storage::<Stackable>().get(item).map(|s| s.quantity).unwrap_or(1);
+        let item = if quantity > 1 {
+            templates::split_stack(&mut self.ecs, item, 1)
+        } else {
+            item
+        };
+
+        let entities = self.ecs.entities();
+        let names = self.ecs.read_storage::<Name>();
+        let mut backpack = self.ecs.write_storage::<InBackpack>();
+        let mut stacks = self.ecs.write_storage::<Stackable>();
+        let existing_stack = stacks.get(item).and_then(|_| {
+            (&entities, &backpack, &stacks, &names)
+                .join()
+                .find(|(_, pack, _, name)| pack.owner == buyer && name.name == item_name)
+                .map(|(stack, ..)| stack)
+        });
+        match existing_stack {
+            Some(stack) => {
+                if let Some(stack) = stacks.get_mut(stack) {
+                    stack.quantity += 1;
+                }
+                entities.delete(item).expect("Unable to merge traded item");
+            }
+            None => {
+                backpack
+                    .insert(item, InBackpack::new(buyer))
+                    .expect("Unable to hand over traded item");
+            }
+        }
+    }
+
+    /// Uses an item from the pack or from under the player's feet, asking for a target first
+    /// if it needs one.
+    fn use_item(&mut self, item: Entity) -> RunState {
+        if self.ecs.read_storage::<Charges>().get(item).is_some_and(|charges| charges.current <= 0) {
+            let name = Name::unwrap(self.ecs.read_storage::<Name>().get(item)).to_string();
+            self.ecs
+                .fetch_mut::<GameLog>()
+                .log(format!("The {} has no charges left.", name));
+            return RunState::AwaitingInput;
+        }
+        let on_floor = self.ecs.read_storage::<Position>().contains(item);
+        if on_floor && !self.player_can_carry(item) {
+            let name = Name::unwrap(self.ecs.read_storage::<Name>().get(item)).to_string();
+            self.ecs
+                .fetch_mut::<GameLog>()
+                .log(format!("The {} is too heavy, you cannot carry any more.", name));
+            return RunState::AwaitingInput;
+        }
+        if let Some(ranged) = self.ecs.read_storage::<Ranged>().get(item) {
+            return RunState::ShowTargeting {
+                range: ranged.range,
+                item,
+            };
+        }
+        self.use_item_on(item, None)
+    }
+
+    fn use_item_on(&mut self, item: Entity, target: Option<Point>) -> RunState {
+        let on_floor = self.ecs.read_storage::<Position>().contains(item);
+        let item = if on_floor { self.take_from_floor(item) } else { item };
+        let mut intent = self.ecs.write_storage::<WantsToUseItem>();
+        let player = self.ecs.fetch::<PlayerEntity>().0;
+        intent
+            .insert(player, WantsToUseItem::on(item, target))
+            .expect("Unable to insert intent");
+        RunState::PlayerTurn
+    }
+
+    /// Lifts a single item off the floor into the player's pack so it can be used straight away.
+    /// `use_item` has already checked that it fits.
+    fn take_from_floor(&mut self, item: Entity) -> Entity {
+        let quantity = self.ecs.read_storage::<Stackable>().get(item).map(|s| s.quantity).unwrap_or(1);
+        let item = if quantity > 1 {
+            templates::split_stack(&mut self.ecs, item, 1)
+        } else {
+            item
+        };
+        let player = self.ecs.fetch::<PlayerEntity>().0;
+        self.ecs.write_storage::<Position>().remove(item);
+        self.ecs
+            .write_storage::<InBackpack>()
+            .insert(item, InBackpack::new(player))
+            .expect("Unable to take item from the floor");
+        item
+    }
+
+    /// Carries out an action picked from the inventory or from the menu of an item on the map.
+    fn item_action(&mut self, item: Entity, action: ItemAction, view: InventoryView) -> RunState {
+        let floor_position = self.ecs.read_storage::<Position>().get(item).map(|pos| Point::new(pos.x, pos.y));
+        match (action, floor_position) {
+            (ItemAction::PickUp, Some(_)) if gui::underfoot(&self.ecs, item) => {
+                player::pick_up(&mut self.ecs, item);
+                RunState::PlayerTurn
+            }
+            (ItemAction::PickUp, Some(pos)) => player::start_walk(&mut self.ecs, pos, Some(item)),
+            (ItemAction::PickUp, None) => RunState::AwaitingInput,
+            (ItemAction::Use | ItemAction::Equip, _) => self.use_item(item),
+            (ItemAction::Throw, _) => RunState::ShowThrowTargeting { item },
+            (ItemAction::Drop, _) => self.start_drop(item),
+            (ItemAction::Examine, Some(cursor)) => RunState::LookCursor { cursor },
+            (ItemAction::Examine, None) => RunState::ExamineItem { item, view },
+        }
+    }
+
+    /// Throws one of `item` at `target`.
+    fn throw_item(&mut self, item: Entity, target: Point) -> RunState {
+        let quantity = self.ecs.read_storage::<Stackable>().get(item).map(|s| s.quantity).unwrap_or(1);
+        let item = if quantity > 1 {
+            templates::split_stack(&mut self.ecs, item, 1)
+        } else {
+            item
+        };
+        let player = self.ecs.fetch::<PlayerEntity>().0;
+        self.ecs
+            .write_storage::<WantsToThrowItem>()
+            .insert(player, WantsToThrowItem { item, target })
+            .expect("Unable to insert intent");
+        RunState::PlayerTurn
+    }
+
+    /// Drops an item, first asking how many if it is a stack.
+    fn start_drop(&mut self, item: Entity) -> RunState {
+        let quantity = self.ecs.read_storage::<Stackable>().get(item).map(|s| s.quantity);
+        match quantity {
+            Some(amount) if amount > 1 => RunState::ShowDropAmount { item, amount },
+            _ => {
+                self.drop_item(item);
+                RunState::PlayerTurn
+            }
+        }
+    }
+
+    /// The terminal only reports left-clicks, so right-clicks are picked out of the raw
+    /// button state: a click is a frame where the button is down and was not before.
+    fn poll_right_click(&mut self) {
+        let down = rltk::INPUT.lock().is_mouse_button_pressed(1);
+        self.right_click = down && !self.right_button_down;
+        self.right_button_down = down;
+    }
+
+    fn drop_item(&mut self, item: Entity) {
+        let mut intent = self.ecs.write_storage::<WantsToDropItem>();
+        let player = self.ecs.fetch::<PlayerEntity>().0;
+        intent
+            .insert(player, WantsToDropItem { item })
+            .expect("Unable to insert intent");
+    }
+
     fn entities_to_remove_on_level_change(&self) -> Vec<Entity> {
         let entities = self.ecs.entities();
         let player = self.ecs.read_storage::<Player>();
         let backpack = self.ecs.read_storage::<InBackpack>();
+        let equipped = self.ecs.read_storage::<Equipped>();
         let player_entity = self.ecs.fetch::<PlayerEntity>().0;
         let mut to_delete = Vec::new();
 
@@ -151,6 +614,10 @@ impl State {
                 should_delete = should_delete && bp.owner != player_entity;
             }
 
+            if let Some(eq) = equipped.get(entity) {
+                should_delete = should_delete && eq.owner != player_entity;
+            }
+
             if should_delete {
                 to_delete.push(entity);
             }
@@ -162,6 +629,17 @@ impl State {
 impl rltk::GameState for State {
     fn tick(&mut self, ctx: &mut rltk::Rltk) {
         let mut new_run_state = { *self.ecs.fetch::<RunState>() };
+        self.poll_right_click();
+        if new_run_state.takes_input() {
+            let walk_step = match new_run_state {
+                RunState::AutoWalk => self.ecs.fetch::<PlayerData>().walk.as_ref().map(|walk| walk.steps),
+                _ => None,
+            };
+            match &mut self.playback {
+                Some(playback) => self.right_click = playback.feed(ctx, walk_step),
+                None => self.ecs.write_resource::<Replay>().record(ctx, self.right_click, walk_step),
+            }
+        }
 
         self.render(ctx);
         gui::draw_ui(&self.ecs, ctx);
@@ -174,12 +652,21 @@ impl rltk::GameState for State {
                         };
                     }
                     MainMenuResult::Selected { selected } => match selected {
-                        MainMenuSelection::NewGame => new_run_state = RunState::PreRun,
+                        MainMenuSelection::NewGame => {
+                            self.setup();
+                            new_run_state = RunState::PreRun;
+                        }
                         MainMenuSelection::LoadGame => {
-                            saveload_system::load_game(&mut self.ecs);
+                            self.resume();
                             new_run_state = RunState::AwaitingInput;
                             saveload_system::delete_save();
                         }
+                        MainMenuSelection::HighScores => {
+                            new_run_state = RunState::ShowHighScores;
+                        }
+                        MainMenuSelection::Options => {
+                            new_run_state = RunState::ShowOptions { selection: 0 };
+                        }
                         MainMenuSelection::Quit => {
                             ctx.quit();
                         }
@@ -187,7 +674,12 @@ impl rltk::GameState for State {
                 }
             }
             RunState::SaveGame => {
-                saveload_system::save_game(&mut self.ecs);
+                // Playback leaves the player's own save alone, as it does for autosaves.
+                if self.playback.is_some() {
+                    saveload_system::rehearse_save(&mut self.ecs);
+                } else {
+                    saveload_system::save_game(&mut self.ecs);
+                }
                 new_run_state = RunState::MainMenu {
                     menu_selection: MainMenuSelection::LoadGame,
                 }
@@ -199,47 +691,93 @@ impl rltk::GameState for State {
             RunState::AwaitingInput => {
                 new_run_state = player_input(self, ctx);
             }
+            RunState::AutoWalk => {
+                new_run_state = player::auto_walk(self, ctx);
+            }
             RunState::PlayerTurn => {
+                player::wear_off_confusion(&mut self.ecs);
+                let turn = self.ecs.fetch::<RunStats>().turns + 1;
+                self.ecs.fetch_mut::<GameLog>().turn = turn;
                 system::run_systems(self);
-                new_run_state = RunState::MonsterTurn;
+                let mut run_stats = self.ecs.write_resource::<RunStats>();
+                run_stats.turns += 1;
+                let victory = run_stats.victory;
+                drop(run_stats);
+                new_run_state = if victory {
+                    self.end_run();
+                    RunState::Victory
+                } else {
+                    RunState::MonsterTurn
+                };
             }
             RunState::MonsterTurn => {
                 system::run_systems(self);
-                new_run_state = RunState::AwaitingInput;
+                // Whatever died in the first move is cleared away before the second, and a
+                // dead player gets no second beating.
+                if self.player_overburdened() && !damage::DamageSystem::delete_the_dead(&mut self.ecs) {
+                    system::run_monster_systems(self);
+                }
+                new_run_state = if self.ecs.fetch::<PlayerData>().walk.is_some() {
+                    RunState::AutoWalk
+                } else {
+                    RunState::AwaitingInput
+                };
             }
-            RunState::ShowInventory => match show_inventory(self, ctx, "Inventory") {
+            RunState::ShowInventory { view } => match show_inventory(self, ctx, "Inventory", view) {
                 ItemMenuResult::NoResponse => {}
                 ItemMenuResult::Cancel => {
                     new_run_state = RunState::AwaitingInput;
                 }
-                ItemMenuResult::Selected(entity) => {
-                    let is_ranged = self.ecs.read_storage::<Ranged>();
-                    if let Some(is_ranged) = is_ranged.get(entity) {
-                        new_run_state = RunState::ShowTargeting {
-                            range: is_ranged.range,
-                            item: entity,
-                        }
-                    } else {
-                        let mut intent = self.ecs.write_storage::<WantsToUseItem>();
-                        let player = self.ecs.fetch::<PlayerEntity>().0;
-                        intent
-                            .insert(player, WantsToUseItem::new(entity))
-                            .expect("Unable to insert intent");
-                        new_run_state = RunState::PlayerTurn;
-                    }
+                ItemMenuResult::ChangeView(view) => {
+                    new_run_state = RunState::ShowInventory { view };
+                }
+                ItemMenuResult::Selected(item) => {
+                    new_run_state = RunState::ItemActions { item, selection: 0, view };
                 }
             },
-            RunState::ShowDropItem => match show_inventory(self, ctx, "Drop which item?") {
+            RunState::ShowDropItem { view } => match show_inventory(self, ctx, "Drop which item?", view) {
                 ItemMenuResult::NoResponse => {}
                 ItemMenuResult::Cancel => {
                     new_run_state = RunState::AwaitingInput;
                 }
+                ItemMenuResult::ChangeView(view) => {
+                    new_run_state = RunState::ShowDropItem { view };
+                }
                 ItemMenuResult::Selected(item) => {
-                    let mut intent = self.ecs.write_storage::<WantsToDropItem>();
-                    let player = self.ecs.fetch::<PlayerEntity>().0;
-                    intent
-                        .insert(player, WantsToDropItem { item })
-                        .expect("Unable to insert intent");
+                    new_run_state = self.start_drop(item);
+                }
+            },
+            RunState::ItemActions { item, selection, view } => match gui::item_action_menu(&self.ecs, ctx, item, selection) {
+                ContextMenuResult::NoResponse { selection } => {
+                    new_run_state = RunState::ItemActions { item, selection, view };
+                }
+                ContextMenuResult::Cancel => {
+                    new_run_state = RunState::ShowInventory { view };
+                }
+                ContextMenuResult::Selected(action) => {
+                    new_run_state = self.item_action(item, action, view);
+                }
+            },
+            RunState::ExamineItem { item, view } => {
+                if gui::examine_item(&self.ecs, ctx, item) == SummaryResult::Dismissed {
+                    new_run_state = RunState::ItemActions { item, selection: 0, view };
+                }
+            }
+            RunState::ShowDropAmount { item, amount } => match gui::drop_amount(self, ctx, item, amount) {
+                AmountMenuResult::NoResponse { amount } => {
+                    new_run_state = RunState::ShowDropAmount { item, amount };
+                }
+                AmountMenuResult::Cancel => {
+                    new_run_state = RunState::AwaitingInput;
+                }
+                AmountMenuResult::Selected { amount } => {
+                    let quantity = self.ecs.read_storage::<Stackable>().get(item).map(|s| s.quantity).unwrap_or(1);
+                    let item = if amount < quantity {
+                        templates::split_stack(&mut self.ecs, item, amount)
+                    } else {
+                        item
+                    };
+                    self.drop_item(item);
                     new_run_state = RunState::PlayerTurn;
                 }
             },
@@ -249,23 +787,180 @@ impl rltk::GameState for State {
                     new_run_state = RunState::AwaitingInput;
                 }
                 TargetMenuResult::Selected(target) => {
-                    let mut intent = self.ecs.write_storage::<WantsToUseItem>();
+                    new_run_state = self.use_item_on(item, Some(target));
+                }
+            },
+            RunState::ShowThrowTargeting { item } => match gui::ranged_target(self, ctx, THROW_RANGE) {
+                TargetMenuResult::NoResponse => {}
+                TargetMenuResult::Cancel => {
+                    new_run_state = RunState::AwaitingInput;
+                }
+                TargetMenuResult::Selected(target) => {
+                    new_run_state = self.throw_item(item, target);
+                }
+            },
+            RunState::ShowVendor { vendor, mode, page } => match gui::show_vendor(self, ctx, vendor, mode, page) {
+                VendorResult::NoResponse => {}
+                VendorResult::Cancel => {
+                    new_run_state = RunState::AwaitingInput;
+                }
+                VendorResult::ToggleMode => {
+                    let mode = match mode {
+                        VendorMode::Buy => VendorMode::Sell,
+                        VendorMode::Sell => VendorMode::Buy,
+                    };
+                    new_run_state = RunState::ShowVendor { vendor, mode, page: 0 };
+                }
+                VendorResult::ChangePage(page) => {
+                    new_run_state = RunState::ShowVendor { vendor, mode, page };
+                }
+                VendorResult::Buy { item, price } => {
                     let player = self.ecs.fetch::<PlayerEntity>().0;
-                    intent
-                        .insert(player, WantsToUseItem::on(item, Some(target)))
-                        .expect("Failed to target use item");
-                    new_run_state = RunState::PlayerTurn;
+                    self.trade(item, price, vendor, player);
+                }
+                VendorResult::Sell { item, price } => {
+                    let player = self.ecs.fetch::<PlayerEntity>().0;
+                    self.trade(item, price, player, vendor);
+                }
+            },
+            RunState::ShowMessageLog { offset } => match gui::show_message_log(&self.ecs, ctx, offset) {
+                MessageLogResult::NoResponse { offset } => {
+                    new_run_state = RunState::ShowMessageLog { offset };
+                }
+                MessageLogResult::Close => {
+                    new_run_state = RunState::AwaitingInput;
+                }
+            },
+            RunState::LookCursor { cursor } => match gui::look_cursor(&self.ecs, ctx, cursor) {
+                LookResult::NoResponse => {}
+                LookResult::Cancel => {
+                    new_run_state = RunState::AwaitingInput;
+                }
+                LookResult::Moved(cursor) => {
+                    new_run_state = RunState::LookCursor { cursor };
+                }
+            },
+            RunState::ItemContext { item, selection } => match gui::item_context_menu(&self.ecs, ctx, item, selection) {
+                ContextMenuResult::NoResponse { selection } => {
+                    new_run_state = RunState::ItemContext { item, selection };
+                }
+                ContextMenuResult::Cancel => {
+                    new_run_state = RunState::AwaitingInput;
+                }
+                ContextMenuResult::Selected(action) => {
+                    new_run_state = self.item_action(item, action, InventoryView::default());
+                }
+            },
+            RunState::ShowCharacter => {
+                if gui::character_sheet(&self.ecs, ctx) == SummaryResult::Dismissed {
+                    new_run_state = RunState::AwaitingInput;
+                }
+            }
+            RunState::Victory => {
+                if gui::victory_screen(&self.ecs, ctx) == SummaryResult::Dismissed {
+                    self.setup();
+                    new_run_state = RunState::default();
+                }
+            }
+            RunState::GameOver => {
+                if gui::game_over_screen(&self.ecs, ctx) == SummaryResult::Dismissed {
+                    self.setup();
+                    new_run_state = RunState::default();
+                }
+            }
+            RunState::ShowHighScores => {
+                if gui::highscore_screen(&self.ecs, ctx) == SummaryResult::Dismissed {
+                    new_run_state = RunState::MainMenu {
+                        menu_selection: MainMenuSelection::HighScores,
+                    };
+                }
+            }
+            RunState::ShowOptions { selection } => match gui::options_menu(ctx, &self.settings, selection) {
+                OptionsResult::NoResponse { selected } => {
+                    new_run_state = RunState::ShowOptions { selection: selected };
+                }
+                OptionsResult::Change { selected, forward } => {
+                    self.settings.change(OPTIONS[selected], forward);
+                    self.apply_settings(ctx);
+                }
+                OptionsResult::Back => {
+                    self.settings.save();
+                    new_run_state = RunState::MainMenu {
+                        menu_selection: MainMenuSelection::Options,
+                    };
+                }
+            },
+            RunState::ShowWizardMenu => {
+                let god_mode = self.ecs.fetch::<WizardMode>().god_mode;
+                match gui::show_wizard_menu(ctx, god_mode) {
+                    WizardMenuResult::NoResponse => {}
+                    WizardMenuResult::Cancel => {
+                        new_run_state = RunState::AwaitingInput;
+                    }
+                    WizardMenuResult::Selected(action) => {
+                        new_run_state = self.wizard_action(action);
+                    }
+                }
+            }
+            RunState::ShowWizardSpawn { page } => match gui::show_spawn_menu(ctx, page) {
+                SpawnMenuResult::NoResponse => {}
+                SpawnMenuResult::Cancel => {
+                    new_run_state = RunState::AwaitingInput;
+                }
+                SpawnMenuResult::ChangePage(page) => {
+                    new_run_state = RunState::ShowWizardSpawn { page };
+                }
+                SpawnMenuResult::Selected(index) => {
+                    new_run_state = RunState::WizardCursor {
+                        action: CursorAction::Spawn(index),
+                    };
+                }
+            },
+            RunState::ShowWizardDepth { depth } => match gui::pick_depth(ctx, depth, FINAL_DEPTH) {
+                AmountMenuResult::NoResponse { amount } => {
+                    new_run_state = RunState::ShowWizardDepth { depth: amount };
+                }
+                AmountMenuResult::Cancel => {
+                    new_run_state = RunState::AwaitingInput;
+                }
+                AmountMenuResult::Selected { amount } => {
+                    self.goto_level(amount);
+                    self.ecs
+                        .fetch_mut::<GameLog>()
+                        .log(format!("You are whisked away to depth {}.", amount));
+                    new_run_state = RunState::PreRun;
+                }
+            },
+            RunState::WizardCursor { action } => match gui::wizard_cursor(self, ctx, action) {
+                TargetMenuResult::NoResponse => {}
+                TargetMenuResult::Cancel => {
+                    new_run_state = RunState::AwaitingInput;
+                }
+                TargetMenuResult::Selected(target) => {
+                    self.wizard_cursor_action(action, target);
+                    new_run_state = RunState::PreRun;
                 }
             },
             RunState::NextLevel => {
                 self.goto_next_level();
+                // Playback repeats the save the recording made, minus the file, so that
+                // entity ids stay in step without overwriting the player's own save.
+                match &self.playback {
+                    Some(playback) if playback.replay.autosave => saveload_system::rehearse_save(&mut self.ecs),
+                    Some(_) => {}
+                    None if self.settings.autosave && !self.wizard => saveload_system::save_game(&mut self.ecs),
+                    None => {}
+                }
                 new_run_state = RunState::PreRun;
             }
         }
 
-        if damage::DamageSystem::delete_the_dead(&mut self.ecs) {
-            self.ecs.delete_all();
-            new_run_state = RunState::default();
+        if new_run_state != RunState::GameOver && damage::DamageSystem::delete_the_dead(&mut self.ecs) {
+            self.ecs
+                .fetch_mut::<GameLog>()
+                .log("You are dead.".to_string());
+            self.end_run();
+            new_run_state = RunState::GameOver;
         }
         {
             let mut run_state = self.ecs.write_resource::<RunState>();
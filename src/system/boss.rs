@@ -0,0 +1,70 @@
+use rltk::Point;
+use specs::{Entities, Join, ReadExpect, ReadStorage, System, WriteExpect, WriteStorage};
+
+use crate::{component::*, resource::{gamelog::GameLog, player::*}, state::RunState};
+
+/// Boss abilities on top of `MonsterAI`: steady regeneration and a fire breath with a cooldown.
+pub struct BossAI {}
+
+pub const BREATH_COOLDOWN: i32 = 5;
+
+impl<'a> System<'a> for BossAI {
+	type SystemData = (
+		ReadExpect<'a, PlayerData>,
+		ReadExpect<'a, PlayerEntity>,
+		ReadExpect<'a, RunState>,
+		WriteExpect<'a, GameLog>,
+		Entities<'a>,
+		ReadStorage<'a, Name>,
+		ReadStorage<'a, Position>,
+		ReadStorage<'a, Viewshed>,
+		ReadStorage<'a, Confusion>,
+		WriteStorage<'a, Boss>,
+		WriteStorage<'a, CombatStats>,
+		WriteStorage<'a, SufferDamage>,
+		ReadStorage<'a, Renderable>
+	);
+
+	fn run(&mut self, data: Self::SystemData) {
+		let (
+			player_data,
+			player,
+			run_state,
+			mut gamelog,
+			entities,
+			names,
+			positions,
+			viewsheds,
+			confusion,
+			mut bosses,
+			mut combat_stats,
+			mut suffer_damage,
+			renderables
+		) = data;
+
+		if *run_state != RunState::MonsterTurn { return; }
+
+		for (entity, boss, pos, viewshed) in (&entities, &mut bosses, &positions, &viewsheds).join() {
+			if let Some(stats) = combat_stats.get_mut(entity) {
+				stats.hp = i32::min(stats.max_hp, stats.hp + 1);
+			}
+
+			boss.cooldown -= 1;
+			if boss.cooldown > 0 || confusion.contains(entity) { continue; }
+			if !viewshed.visible_tiles.contains(&player_data.position) { continue; }
+
+			let distance = rltk::DistanceAlg::Pythagoras.distance2d(Point::new(pos.x, pos.y), player_data.position);
+			if distance < 1.5 || distance > boss.breath_range as f32 { continue; }
+
+			let name = Name::unwrap(names.get(entity));
+			SufferDamage::new_damage(&mut suffer_damage, player.0, boss.breath_damage, format!("{}'s fire breath", name));
+			boss.cooldown = BREATH_COOLDOWN;
+			gamelog.entry()
+				.name(name, renderables.get(entity))
+				.text(" breathes fire at you, for ")
+				.damage(boss.breath_damage)
+				.text("!")
+				.log();
+		}
+	}
+}
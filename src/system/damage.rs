@@ -1,20 +1,29 @@
-use rltk::console;
-use specs::{Entity, Join, System, World, WorldExt, WriteStorage};
+use rltk::RandomNumberGenerator;
+use specs::{Entities, Entity, Join, ReadExpect, ReadStorage, System, World, WorldExt, WriteExpect, WriteStorage};
 
-use crate::{component::{CombatStats, Name, Player, SufferDamage}, resource::gamelog};
+use crate::{component::{CombatStats, Equipped, InBackpack, LeavesCorpse, LootTable, Monster, Name, Player, Position, Purse, Renderable, SufferDamage}, resource::{gamelog, map::Map, run_stats::RunStats, spawner, wizard::WizardMode}, templates};
 
 pub struct DamageSystem;
 
 impl<'a> System<'a> for DamageSystem {
 	type SystemData = (
+		Entities<'a>,
+		ReadExpect<'a, WizardMode>,
+		WriteExpect<'a, RunStats>,
+		ReadStorage<'a, Player>,
 		WriteStorage<'a, CombatStats>,
 		WriteStorage<'a, SufferDamage>
 	);
 
 	fn run(&mut self, data: Self::SystemData) {
-		let (mut stats, mut damage) = data;
-		for (stats, damage) in (&mut stats, &damage).join() {
+		let (entities, wizard, mut run_stats, players, mut stats, mut damage) = data;
+		for (entity, stats, damage) in (&entities, &mut stats, &damage).join() {
+			if wizard.god_mode && players.contains(entity) { continue; }
+			let was_alive = stats.hp > 0;
 			stats.hp -= damage.amount.iter().sum::<i32>();
+			if was_alive && stats.hp <= 0 && players.contains(entity) {
+				run_stats.cause_of_death = damage.sources.last().cloned();
+			}
 		}
 		damage.clear();
 	}
@@ -24,26 +33,86 @@ impl DamageSystem {
 	pub fn delete_the_dead(ecs: &mut World) -> bool {
 		let mut player_died = false;
 		let mut dead: Vec<Entity> = Vec::new();
+		let mut dropped_gold = Vec::new();
+		let mut corpses = Vec::new();
+		let mut loot = Vec::new();
 		{
 			let combat_stats = ecs.read_storage::<CombatStats>();
 			let entities = ecs.entities();
 			let players = ecs.read_storage::<Player>();
 			let mut game_log = ecs.fetch_mut::<gamelog::GameLog>();
 			let names = ecs.read_storage::<Name>();
+			let renderables = ecs.read_storage::<Renderable>();
+			let purses = ecs.read_storage::<Purse>();
+			let mut positions = ecs.write_storage::<Position>();
+			let mut backpack = ecs.write_storage::<InBackpack>();
+			let mut equipped = ecs.write_storage::<Equipped>();
+			let loot_tables = ecs.read_storage::<LootTable>();
+			let leaves_corpse = ecs.read_storage::<LeavesCorpse>();
+			let monsters = ecs.read_storage::<Monster>();
+			let mut run_stats = ecs.write_resource::<RunStats>();
 			for (entity, stats) in (&entities, &combat_stats).join() {
 				if stats.hp > 0 { continue; }
 				if let Some(_) = players.get(entity) { 
-					console::log("you are dead.");
 					player_died = true;
 					continue; 
 				}
 				if let Some(name) = names.get(entity) {
-					game_log.log(format!("{} died.", name.name));
+					game_log.entry().name(&name.name, renderables.get(entity)).text(" died.").log();
 				}
 				dead.push(entity);
+				if monsters.contains(entity) {
+					run_stats.record_kill(Name::unwrap(names.get(entity)));
+				}
+
+				let Some(pos) = positions.get(entity).cloned() else { continue; };
+				if let Some(purse) = purses.get(entity) {
+					if purse.gold > 0 {
+						dropped_gold.push((pos.x, pos.y, purse.gold));
+					}
+				}
+				if let Some(table) = loot_tables.get(entity) {
+					loot.push((pos.x, pos.y, table.clone()));
+				}
+				if leaves_corpse.contains(entity) {
+					corpses.push((pos.x, pos.y, Name::unwrap(names.get(entity)).to_string()));
+				}
+
+				let mut carried = (&entities, &backpack).join()
+					.filter(|(_, pack)| pack.owner == entity)
+					.map(|(item, _)| item)
+					.collect::<Vec<_>>();
+				carried.extend((&entities, &equipped).join()
+					.filter(|(_, worn)| worn.owner == entity)
+					.map(|(item, _)| item));
+				for item in carried {
+					backpack.remove(item);
+					equipped.remove(item);
+					positions.insert(item, pos.clone()).expect("Unable to drop carried item");
+				}
 			}
 		}
 		ecs.delete_entities(&dead).expect("Unable to delete.");
+		for (x, y, name) in corpses {
+			templates::corpse(ecs, x, y, name);
+		}
+		for (x, y, amount) in dropped_gold {
+			templates::gold(ecs, x, y, amount);
+		}
+		for (x, y, table) in loot {
+			let drop = {
+				let depth = ecs.fetch::<Map>().depth;
+				let mut rng = ecs.write_resource::<RandomNumberGenerator>();
+				if rng.roll_dice(1, 100) <= table.chance {
+					spawner::loot_table(table.table, depth).roll(&mut rng)
+				} else {
+					None
+				}
+			};
+			if let Some(entry) = drop {
+				(entry.spawner)(ecs, x, y);
+			}
+		}
 		player_died
 	}
-}
\ No newline at end of file
+}
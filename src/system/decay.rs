@@ -0,0 +1,26 @@
+use specs::{Entities, Join, ReadExpect, System, WriteStorage};
+
+use crate::{component::Decays, state::RunState};
+
+pub struct DecaySystem;
+
+impl<'a> System<'a> for DecaySystem {
+	type SystemData = (
+		ReadExpect<'a, RunState>,
+		Entities<'a>,
+		WriteStorage<'a, Decays>
+	);
+
+	fn run(&mut self, data: Self::SystemData) {
+		let (run_state, entities, mut decays) = data;
+
+		if *run_state != RunState::MonsterTurn { return; }
+
+		for (entity, decay) in (&entities, &mut decays).join() {
+			decay.turns -= 1;
+			if decay.turns < 1 {
+				entities.delete(entity).expect("Unable to delete decayed entity");
+			}
+		}
+	}
+}
@@ -0,0 +1,51 @@
+use std::{fs, path::Path};
+use serde::{Deserialize, Serialize};
+use specs::World;
+use crate::resource::{map::Map, run_stats::RunStats};
+
+use super::morgue;
+
+const HIGHSCORE_FILE_NAME: &str = "./highscores.json";
+pub const MAX_HIGHSCORES: usize = 10;
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct HighScore {
+	pub score: i32,
+	pub depth: i32,
+	pub turns: i32,
+	pub kills: i32,
+	pub victory: bool,
+	pub cause_of_death: Option<String>,
+}
+
+pub fn load_highscores() -> Vec<HighScore> {
+	if !Path::new(HIGHSCORE_FILE_NAME).exists() {
+		return Vec::new();
+	}
+	fs::read_to_string(HIGHSCORE_FILE_NAME)
+		.ok()
+		.and_then(|data| serde_json::from_str(&data).ok())
+		.unwrap_or_default()
+}
+
+/// Adds the finished run to the table, keeping only the best `MAX_HIGHSCORES`.
+pub fn record_highscore(ecs: &World) {
+	let stats = ecs.fetch::<RunStats>();
+	let depth = ecs.fetch::<Map>().depth;
+	let entry = HighScore {
+		score: morgue::score(&stats, depth),
+		depth,
+		turns: stats.turns,
+		kills: stats.total_kills(),
+		victory: stats.victory,
+		cause_of_death: stats.cause_of_death.clone(),
+	};
+
+	let mut scores = load_highscores();
+	scores.push(entry);
+	scores.sort_by_key(|s| std::cmp::Reverse(s.score));
+	scores.truncate(MAX_HIGHSCORES);
+	if let Ok(data) = serde_json::to_string_pretty(&scores) {
+		let _ = fs::write(HIGHSCORE_FILE_NAME, data);
+	}
+}
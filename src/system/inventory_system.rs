@@ -1,7 +1,7 @@
-use rltk::console;
+use rltk::{console, Point};
 use specs::{storage::GenericReadStorage, Entities, Join, ReadExpect, ReadStorage, System, WriteExpect, WriteStorage};
 
-use crate::{component::{AreaOfEffect, CombatStats, Confusion, Consumable, InBackpack, InflictsDamage, Name, Position, ProvidesHealing, SufferDamage, WantsToDropItem, WantsToUseItem}, resource::{gamelog::GameLog, map::Map, player::PlayerEntity}};
+use crate::{component::{AreaOfEffect, Charges, CombatStats, Confusion, Consumable, Equippable, Equipped, InBackpack, InflictsDamage, MeleePowerBonus, Name, Position, ProvidesHealing, QuestItem, Recharge, Renderable, Stackable, SufferDamage, WantsToDropItem, WantsToThrowItem, WantsToUseItem}, resource::{gamelog::GameLog, map::{Map, TileType}, player::PlayerEntity, run_stats::RunStats}};
 
 pub struct ItemUseSystem;
 
@@ -20,6 +20,16 @@ impl<'a> System<'a> for ItemUseSystem {
 		WriteStorage<'a, Confusion>,
 		WriteStorage<'a, CombatStats>,
 		WriteStorage<'a, SufferDamage>,
+		WriteStorage<'a, Charges>,
+		ReadStorage<'a, Recharge>,
+		WriteStorage<'a, InBackpack>,
+		WriteStorage<'a, Stackable>,
+		ReadStorage<'a, Equippable>,
+		WriteStorage<'a, Equipped>,
+		ReadStorage<'a, Position>,
+		ReadStorage<'a, QuestItem>,
+		ReadStorage<'a, Renderable>,
+		WriteExpect<'a, RunStats>,
 	);
 
 	fn run(&mut self, data: Self::SystemData) {
@@ -37,13 +47,96 @@ impl<'a> System<'a> for ItemUseSystem {
 			mut confusion,
 			mut combat_stats,
 			mut suffer_damage,
+			mut charges,
+			recharge,
+			mut backpack,
+			mut stackable,
+			equippable,
+			mut equipped,
+			positions,
+			quest_items,
+			renderables,
+			mut run_stats,
 		) = data;
 
 		for (entity, use_item) in (&entities, &wants_drink).join() {
+			let user_visible = positions.get(entity)
+				.is_some_and(|pos| map.visible_tiles[map.xy_idx(pos.x, pos.y)]);
+			if entity != player.0 && user_visible {
+				let user_name = Name::unwrap(names.get(entity));
+				let item_name = Name::unwrap(names.get(use_item.item));
+				gamelog.entry()
+					.name(user_name, renderables.get(entity))
+					.text(" uses the ")
+					.name(item_name, renderables.get(use_item.item))
+					.text(".")
+					.log();
+			}
+
+			if quest_items.contains(use_item.item) {
+				if entity == player.0 {
+					let name = Name::unwrap(names.get(use_item.item));
+					gamelog.entry()
+						.text("You raise the ")
+						.name(name, renderables.get(use_item.item))
+						.text(" and its light fills the dungeon. You have won!")
+						.log();
+					run_stats.victory = true;
+				}
+				continue;
+			}
+
+			if let Some(can_equip) = equippable.get(use_item.item) {
+				let slot = can_equip.slot;
+				let to_unequip = (&entities, &equipped).join()
+					.filter(|(_, worn)| worn.owner == entity && worn.slot == slot)
+					.map(|(item, _)| item)
+					.collect::<Vec<_>>();
+				for item in to_unequip {
+					equipped.remove(item);
+					backpack.insert(item, InBackpack::new(entity)).expect("Unable to unequip item");
+					if entity == player.0 {
+						gamelog.entry()
+							.text("You unequip the ")
+							.name(Name::unwrap(names.get(item)), renderables.get(item))
+							.text(".")
+							.log();
+					}
+				}
+				backpack.remove(use_item.item);
+				equipped.insert(use_item.item, Equipped { owner: entity, slot }).expect("Unable to equip item");
+				if entity == player.0 {
+					gamelog.entry()
+						.text("You equip the ")
+						.name(Name::unwrap(names.get(use_item.item)), renderables.get(use_item.item))
+						.text(".")
+						.log();
+				}
+				continue;
+			}
+
+			if let Some(item_charges) = charges.get(use_item.item) {
+				if item_charges.current <= 0 {
+					if entity == player.0 {
+						let name = Name::unwrap(names.get(use_item.item));
+						gamelog.entry()
+							.text("The ")
+							.name(name, renderables.get(use_item.item))
+							.text(" fizzles, it has no charges left.")
+							.log();
+					}
+					continue;
+				}
+			}
+
+			// Equipping, raising the Amulet and fizzling above are not counted as using an item.
+			if entity == player.0 {
+				run_stats.items_used += 1;
+			}
 
 			let mut targets = Vec::new();
 			match use_item.target {
-				None => { targets.push(player.0); }
+				None => { targets.push(entity); }
 				Some(target) => {
 					match aoe.get(use_item.item) {
 						None => {
@@ -72,7 +165,13 @@ impl<'a> System<'a> for ItemUseSystem {
 						stats.hp = i32::min(stats.max_hp, stats.hp + healing.heal_amount);
 						if entity == player.0 {
 							let name = Name::unwrap(names.get(use_item.item));
-							gamelog.log(format!("You drink the {}, healing {}, hp", name, healing.heal_amount));
+							gamelog.entry()
+								.text("You drink the ")
+								.name(name, renderables.get(use_item.item))
+								.text(", healing ")
+								.healing(healing.heal_amount)
+								.text(".")
+								.log();
 						}
 					}
 				}
@@ -81,11 +180,20 @@ impl<'a> System<'a> for ItemUseSystem {
 
 			if let Some(damage) = damages.get(use_item.item) {
 				for target in targets.iter() {
-					SufferDamage::new_damage(&mut suffer_damage, *target, damage.damage);
+					let source = format!("{}'s {}", Name::unwrap(names.get(entity)), Name::unwrap(names.get(use_item.item)));
+					SufferDamage::new_damage(&mut suffer_damage, *target, damage.damage, source);
 					if entity == player.0 {
 						let mob_name = Name::unwrap(names.get(*target));
 						let item_name = Name::unwrap(names.get(use_item.item));
-						gamelog.log(format!("You use {} on {}, inflicting {} hp.", item_name, mob_name, damage.damage));
+						gamelog.entry()
+							.text("You use ")
+							.name(item_name, renderables.get(use_item.item))
+							.text(" on ")
+							.name(mob_name, renderables.get(*target))
+							.text(", inflicting ")
+							.damage(damage.damage)
+							.text(".")
+							.log();
 					}
 				}
 			}
@@ -98,7 +206,13 @@ impl<'a> System<'a> for ItemUseSystem {
 						if entity == player.0 {
 							let name = Name::unwrap(names.get(*mob));
 							let item = Name::unwrap(names.get(use_item.item));
-							gamelog.log(format!("You use {} on {}, confusing them.", item, name));
+							gamelog.entry()
+								.text("You use ")
+								.name(item, renderables.get(use_item.item))
+								.text(" on ")
+								.name(name, renderables.get(*mob))
+								.text(", confusing them.")
+								.log();
 						}
 					}
 				}
@@ -107,8 +221,49 @@ impl<'a> System<'a> for ItemUseSystem {
 						.expect("Unable to insert confusion");
 				}
 			}
+			if recharge.contains(use_item.item) {
+				for (item, pack, item_charges) in (&entities, &backpack, &mut charges).join() {
+					if pack.owner != entity { continue; }
+					item_charges.current = item_charges.max;
+					if entity == player.0 {
+						let name = Name::unwrap(names.get(item));
+						gamelog.entry()
+							.text("Your ")
+							.name(name, renderables.get(item))
+							.text(" hums with renewed energy.")
+							.log();
+					}
+				}
+			}
+
+			if let Some(item_charges) = charges.get_mut(use_item.item) {
+				item_charges.current -= 1;
+				if item_charges.current <= 0 {
+					let name = Name::unwrap(names.get(use_item.item));
+					if item_charges.crumbles {
+						entities.delete(use_item.item).expect("Delete failed");
+						if entity == player.0 {
+							gamelog.entry()
+								.text("The ")
+								.name(name, renderables.get(use_item.item))
+								.text(" crumbles to dust.")
+								.log();
+						}
+					} else if entity == player.0 {
+						gamelog.entry()
+							.text("The ")
+							.name(name, renderables.get(use_item.item))
+							.text(" is out of charges.")
+							.log();
+					}
+				}
+			}
+
 			if consumables.contains(use_item.item) {
-				entities.delete(use_item.item).expect("Delete failed");				
+				match stackable.get_mut(use_item.item) {
+					Some(stack) if stack.quantity > 1 => stack.quantity -= 1,
+					_ => entities.delete(use_item.item).expect("Delete failed"),
+				}
 			}
 		}
 
@@ -127,7 +282,8 @@ impl<'a> System<'a> for ItemDropSystem {
 		WriteStorage<'a, WantsToDropItem>,
 		ReadStorage<'a, Name>,
 		WriteStorage<'a, Position>,
-		WriteStorage<'a, InBackpack>
+		WriteStorage<'a, InBackpack>,
+		ReadStorage<'a, Renderable>
 	);
 
 	fn run(&mut self, data: Self::SystemData) {
@@ -138,7 +294,8 @@ impl<'a> System<'a> for ItemDropSystem {
 			mut wants_drop,
 			names,
 			mut positions,
-			mut backpack
+			mut backpack,
+			renderables
 		) = data;
 
 		for (entity, to_drop) in (&entities, &wants_drop).join() {
@@ -152,9 +309,106 @@ impl<'a> System<'a> for ItemDropSystem {
 
 			if entity == player.0 {
 				let name = Name::unwrap(names.get(to_drop.item));
-				gamelog.log(format!("You drop the {}.", name));
+				gamelog.entry()
+					.text("You drop the ")
+					.name(name, renderables.get(to_drop.item))
+					.text(".")
+					.log();
 			}
 		}
 		wants_drop.clear();
 	}
-}
\ No newline at end of file
+}
+
+
+/// Damage a thrown item does to whoever it hits, on top of any power bonus it has as a weapon.
+const THROW_DAMAGE: i32 = 1;
+
+pub struct ItemThrowSystem;
+
+impl<'a> System<'a> for ItemThrowSystem {
+	type SystemData = (
+		ReadExpect<'a, PlayerEntity>,
+		ReadExpect<'a, Map>,
+		WriteExpect<'a, GameLog>,
+		Entities<'a>,
+		WriteStorage<'a, WantsToThrowItem>,
+		ReadStorage<'a, Name>,
+		WriteStorage<'a, Position>,
+		WriteStorage<'a, InBackpack>,
+		ReadStorage<'a, CombatStats>,
+		ReadStorage<'a, MeleePowerBonus>,
+		WriteStorage<'a, SufferDamage>,
+		ReadStorage<'a, Renderable>
+	);
+
+	fn run(&mut self, data: Self::SystemData) {
+		let (
+			player,
+			map,
+			mut gamelog,
+			entities,
+			mut wants_throw,
+			names,
+			mut positions,
+			mut backpack,
+			combat_stats,
+			power_bonus,
+			mut suffer_damage,
+			renderables
+		) = data;
+
+		for (entity, throw) in (&entities, &wants_throw).join() {
+			let Some(start) = positions.get(entity).map(|pos| Point::new(pos.x, pos.y)) else {
+				continue;
+			};
+
+			// The item flies towards the target, landing short of any wall and at the feet of the first creature in its way.
+			let mut landing = start;
+			let mut victim = None;
+			for step in rltk::line2d(rltk::LineAlg::Bresenham, start, throw.target).into_iter().skip(1) {
+				if !map.in_bounds(step.x, step.y) || map.tiles[map.xy_idx(step.x, step.y)] == TileType::Wall {
+					break;
+				}
+				landing = step;
+				victim = map.tile_content[map.xy_idx(step.x, step.y)].iter()
+					.copied()
+					.find(|other| *other != entity && combat_stats.contains(*other));
+				if victim.is_some() {
+					break;
+				}
+			}
+			positions.insert(throw.item, Position::new(landing.x, landing.y)).expect("Failed to land thrown item");
+			backpack.remove(throw.item);
+
+			let item_name = Name::unwrap(names.get(throw.item));
+			match victim {
+				Some(victim) => {
+					let damage = THROW_DAMAGE + power_bonus.get(throw.item).map(|bonus| bonus.power).unwrap_or_default();
+					let source = format!("{}'s thrown {}", Name::unwrap(names.get(entity)), item_name);
+					SufferDamage::new_damage(&mut suffer_damage, victim, damage, source);
+					if entity == player.0 {
+						gamelog.entry()
+							.text("You throw the ")
+							.name(item_name, renderables.get(throw.item))
+							.text(" at ")
+							.name(Name::unwrap(names.get(victim)), renderables.get(victim))
+							.text(", inflicting ")
+							.damage(damage)
+							.text(".")
+							.log();
+					}
+				}
+				None if entity == player.0 => {
+					gamelog.entry()
+						.text("You throw the ")
+						.name(item_name, renderables.get(throw.item))
+						.text(".")
+						.log();
+				}
+				None => {}
+			}
+		}
+		wants_throw.clear();
+	}
+}
@@ -1,39 +1,123 @@
-use specs::{Entity, Join, ReadExpect, ReadStorage, System, WriteExpect, WriteStorage};
+use specs::{Entities, Join, ReadExpect, ReadStorage, System, WriteExpect, WriteStorage};
 
-use crate::{component::*, resource::{gamelog::{self, GameLog}, player::PlayerEntity}};
+use crate::{component::*, resource::{gamelog::GameLog, map::Map, palette::Color, player::PlayerEntity}};
+
+/// Multiple of the carry capacity above which nothing more can be picked up.
+pub const MAX_BURDEN: f32 = 1.5;
 
 pub struct ItemCollectionSystem;
 
 impl <'a> System<'a> for ItemCollectionSystem {
 	type SystemData = (
 		ReadExpect<'a, PlayerEntity>,
+		ReadExpect<'a, Map>,
 		WriteExpect<'a, GameLog>,
+		Entities<'a>,
 		WriteStorage<'a, WantsToPickupItem>,
 		WriteStorage<'a, Position>,
 		ReadStorage<'a, Name>,
-		WriteStorage<'a, InBackpack>
+		WriteStorage<'a, InBackpack>,
+		WriteStorage<'a, Stackable>,
+		ReadStorage<'a, Weight>,
+		ReadStorage<'a, CombatStats>,
+		ReadStorage<'a, Gold>,
+		WriteStorage<'a, Purse>,
+		ReadStorage<'a, Renderable>,
+		ReadStorage<'a, Equipped>
 	);
 
 	fn run(&mut self, data: Self::SystemData) {
 		let (
-			player, 
-			mut game_log, 
-			mut wants_to_pickup, 
+			player,
+			map,
+			mut game_log,
+			entities,
+			mut wants_to_pickup,
 			mut position,
 			name,
-			mut backpack
+			mut backpack,
+			mut stackable,
+			weights,
+			combat_stats,
+			gold,
+			mut purses,
+			renderables,
+			equipped
 		) = data;
 
 		for pickup in wants_to_pickup.join() {
+			if let Some(gold) = gold.get(pickup.item) {
+				if let Some(purse) = purses.get_mut(pickup.collected_by) {
+					purse.gold += gold.amount;
+					entities.delete(pickup.item).expect("Failed to pick up gold");
+					if pickup.collected_by == player.0 {
+						game_log.entry()
+							.text("You pick up ")
+							.colored(Color::Gold, format!("{} gold", gold.amount))
+							.text(".")
+							.log();
+					}
+				}
+				continue;
+			}
+
+			let item_name = Name::unwrap(name.get(pickup.item)).to_string();
+			// Stacked items are deleted below, so hold on to how the item is drawn.
+			let item_render = renderables.get(pickup.item).cloned();
+			let capacity = combat_stats.get(pickup.collected_by).map(|stats| stats.carry_capacity());
+			let carried = Weight::carried_by(pickup.collected_by, &entities, &backpack, &equipped, &weights, &stackable);
+			let quantity = stackable.get(pickup.item).map(|s| s.quantity).unwrap_or(1);
+			let item_weight = weights.get(pickup.item).map(|w| w.weight * quantity as f32).unwrap_or(0.0);
+
+			if let Some(capacity) = capacity {
+				if carried + item_weight > capacity * MAX_BURDEN {
+					if pickup.collected_by == player.0 {
+						game_log.entry()
+							.text("The ")
+							.name(&item_name, item_render.as_ref())
+							.text(" is too heavy, you cannot carry any more.")
+							.log();
+					}
+					continue;
+				}
+				if carried <= capacity && carried + item_weight > capacity && pickup.collected_by == player.0 {
+					game_log.log("You are overburdened and slow down.".to_string());
+				}
+			}
+
 			position.remove(pickup.item);
-			backpack.insert(pickup.item, InBackpack::new(pickup.collected_by)).expect("Failed to insert into backpack");
+			let existing_stack = stackable.get(pickup.item).and_then(|_| {
+				(&entities, &backpack, &stackable, &name).join()
+					.find(|(_, pack, _, stack_name)| pack.owner == pickup.collected_by && stack_name.name == item_name)
+					.map(|(stack, ..)| stack)
+			});
+
+			if let Some(stack) = existing_stack {
+				if let Some(stack) = stackable.get_mut(stack) {
+					stack.quantity += quantity;
+				}
+				entities.delete(pickup.item).expect("Failed to merge item into stack");
+			} else {
+				backpack.insert(pickup.item, InBackpack::new(pickup.collected_by)).expect("Failed to insert into backpack");
+			}
 
 			if pickup.collected_by == player.0 {
-				let name = name.get(pickup.item).map(|name| name.name.to_string()).unwrap_or("???".to_string());
-				game_log.log(format!("You pick up the {}.", name));
+				game_log.entry()
+					.text("You pick up the ")
+					.name(&item_name, item_render.as_ref())
+					.text(".")
+					.log();
+			} else if position.get(pickup.collected_by).is_some_and(|pos| map.visible_tiles[map.xy_idx(pos.x, pos.y)]) {
+				let collector = Name::unwrap(name.get(pickup.collected_by));
+				game_log.entry()
+					.name(collector, renderables.get(pickup.collected_by))
+					.text(" picks up the ")
+					.name(&item_name, item_render.as_ref())
+					.text(".")
+					.log();
 			}
 		}
 
 		wants_to_pickup.clear();
 	}
-}
\ No newline at end of file
+}
@@ -1,7 +1,7 @@
 use rltk::console;
 use specs::{storage::GenericReadStorage, Entities, Join, ReadStorage, System, WriteExpect, WriteStorage};
 
-use crate::{component::{CombatStats, Name, SufferDamage, WantsToMelee}, resource::gamelog::GameLog};
+use crate::{component::{CombatStats, DefenseBonus, Equipped, MeleePowerBonus, Name, Renderable, SufferDamage, WantsToMelee}, resource::gamelog::GameLog};
 
 pub struct MeleeCombatSystem;
 
@@ -12,7 +12,11 @@ impl<'a> System<'a> for MeleeCombatSystem {
 		WriteStorage<'a, WantsToMelee>,
 		ReadStorage<'a, Name>,
 		ReadStorage<'a, CombatStats>,
-		WriteStorage<'a, SufferDamage>
+		WriteStorage<'a, SufferDamage>,
+		ReadStorage<'a, Equipped>,
+		ReadStorage<'a, MeleePowerBonus>,
+		ReadStorage<'a, DefenseBonus>,
+		ReadStorage<'a, Renderable>
 	);
 
 	fn run(&mut self, data: Self::SystemData) {
@@ -22,20 +26,46 @@ impl<'a> System<'a> for MeleeCombatSystem {
 			mut wants_melee,
 			names,
 			combat_stats,
-			mut inflict_damage
+			mut inflict_damage,
+			equipped,
+			power_bonus,
+			defense_bonus,
+			renderables
 		) = data;
 
-		for (_entity, wants_melee, name, stats) in (&entities, &wants_melee, &names, &combat_stats).join() {
+		for (entity, wants_melee, name, stats) in (&entities, &wants_melee, &names, &combat_stats).join() {
 			if stats.hp <= 0 { continue; }
 			let target_stats = combat_stats.get(wants_melee.target).unwrap();
 			if target_stats.hp <= 0 { continue; }
 			let target_name = names.get(wants_melee.target).unwrap();
-			let damage = i32::max(0, stats.power - target_stats.defense);
+			let power = stats.power + (&equipped, &power_bonus).join()
+				.filter(|(equipped, _)| equipped.owner == entity)
+				.map(|(_, bonus)| bonus.power)
+				.sum::<i32>();
+			let defense = target_stats.defense + (&equipped, &defense_bonus).join()
+				.filter(|(equipped, _)| equipped.owner == wants_melee.target)
+				.map(|(_, bonus)| bonus.defense)
+				.sum::<i32>();
+			let damage = i32::max(0, power - defense);
+			let attacker = renderables.get(entity);
+			let target = renderables.get(wants_melee.target);
 			if damage == 0 {
-				game_log.log(format!("{} is unable to hurt {}", &name.name, &target_name.name));
+				game_log.entry()
+					.name(&name.name, attacker)
+					.text(" is unable to hurt ")
+					.name(&target_name.name, target)
+					.text(".")
+					.log();
 			} else {
-				game_log.log(format!("{} is hits {}, for {} hp.", &name.name, &target_name.name, damage));
-				SufferDamage::new_damage(&mut inflict_damage, wants_melee.target, damage);
+				game_log.entry()
+					.name(&name.name, attacker)
+					.text(" hits ")
+					.name(&target_name.name, target)
+					.text(", for ")
+					.damage(damage)
+					.text(".")
+					.log();
+				SufferDamage::new_damage(&mut inflict_damage, wants_melee.target, damage, &name.name);
 			}
 		}
 		wants_melee.clear();
@@ -4,13 +4,19 @@ use crate::state::State;
 
 mod visibility;
 mod monster;
+mod monster_items;
+mod boss;
 mod map_indexing;
 mod melee_combat;
-mod item_pickup;
+pub mod item_pickup;
 mod inventory_system;
+mod decay;
 pub mod damage;
 pub mod player;
 pub mod saveload_system;
+pub mod morgue;
+pub mod highscore;
+pub mod replay;
 
 macro_rules! run_now {
 	($system: expr, $ecs: expr) => {
@@ -21,13 +27,29 @@ macro_rules! run_now {
 
 pub fn run_systems(state: &mut State) {
 	run_now!(visibility::VisibilitySystem {}, &state.ecs);
+	run_now!(monster_items::MonsterItemAI {}, &state.ecs);
 	run_now!(monster::MonsterAI {}, &state.ecs);
+	run_now!(boss::BossAI {}, &state.ecs);
 	run_now!(map_indexing::MapIndexingSystem {}, &state.ecs);
 	run_now!(melee_combat::MeleeCombatSystem {}, &state.ecs);
 	run_now!(damage::DamageSystem {}, &state.ecs);
 	run_now!(item_pickup::ItemCollectionSystem {}, &state.ecs);
 	run_now!(inventory_system::ItemUseSystem {}, &state.ecs);
 	run_now!(inventory_system::ItemDropSystem {}, &state.ecs);
+	run_now!(inventory_system::ItemThrowSystem {}, &state.ecs);
+	run_now!(decay::DecaySystem {}, &state.ecs);
+
+	state.ecs.maintain();
+}
+
+/// The extra move monsters get on an overburdened player: moving and fighting only, so
+/// nothing else ages, regenerates or acts twice.
+pub fn run_monster_systems(state: &mut State) {
+	run_now!(visibility::VisibilitySystem {}, &state.ecs);
+	run_now!(monster::MonsterAI {}, &state.ecs);
+	run_now!(map_indexing::MapIndexingSystem {}, &state.ecs);
+	run_now!(melee_combat::MeleeCombatSystem {}, &state.ecs);
+	run_now!(damage::DamageSystem {}, &state.ecs);
 
 	state.ecs.maintain();
 }
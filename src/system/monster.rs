@@ -1,7 +1,7 @@
 use rltk::{console, Point};
 use specs::{Entities, Join, ReadExpect, ReadStorage, System, WriteExpect, WriteStorage};
 
-use crate::{component::*, resource::{ map::Map, player::*}, state::RunState};
+use crate::{component::*, resource::{ map::Map, overlay::DebugOverlays, player::*}, state::RunState};
 
 pub struct MonsterAI {}
 
@@ -11,13 +11,16 @@ impl<'a> System<'a> for MonsterAI {
 		ReadExpect<'a, PlayerData>,
 		ReadExpect<'a, PlayerEntity>,
 		ReadExpect<'a, RunState>,
+		WriteExpect<'a, DebugOverlays>,
 		Entities<'a>,
 		ReadStorage<'a, Name>,
 		ReadStorage<'a, Monster>,
 		WriteStorage<'a, Confusion>,
 		WriteStorage<'a, Viewshed>,
 		WriteStorage<'a, Position>,
-		WriteStorage<'a, WantsToMelee>
+		WriteStorage<'a, WantsToMelee>,
+		ReadStorage<'a, WantsToUseItem>,
+		ReadStorage<'a, WantsToPickupItem>
 	);
 
 	fn run(&mut self, data: Self::SystemData) {
@@ -26,16 +29,20 @@ impl<'a> System<'a> for MonsterAI {
 			player_data,
 			player,
 			run_state,
+			mut overlays,
 			entities,
 			name,
 			monster,
 			mut confusion,
 			mut viewshed, 
 			mut position,
-			mut wants_to_melee
+			mut wants_to_melee,
+			wants_to_use,
+			wants_to_pickup
 		) = data;
 
 		if *run_state != RunState::MonsterTurn { return; }
+		overlays.monster_paths.clear();
 
 		for (entity, viewshed, name, _monster, position) in (&entities, &mut viewshed, &name, &monster, &mut position).join() {
 			let mut can_act = true;
@@ -46,6 +53,7 @@ impl<'a> System<'a> for MonsterAI {
 			}
 
 			if !can_act { continue; }
+			if wants_to_use.contains(entity) || wants_to_pickup.contains(entity) { continue; }
 
 			let distance = rltk::DistanceAlg::Pythagoras.distance2d(
 				Point::new(position.x, position.y), 
@@ -66,6 +74,7 @@ impl<'a> System<'a> for MonsterAI {
 			);
 
 			if path.success && path.steps.len() > 1 {
+				overlays.monster_paths.insert(entity, path.steps.clone());
 				let mut idx = map.xy_idx(position.x, position.y);
 				map.blocked[idx] = false;
 				position.x = path.steps[1] as i32 % map.width;				
@@ -0,0 +1,117 @@
+use rltk::Point;
+use specs::{Entities, Entity, Join, ReadExpect, ReadStorage, System, WriteStorage};
+
+use crate::{component::*, resource::{map::Map, player::*}, state::RunState};
+
+/// Lets monsters pick up, drink, read and equip items before `MonsterAI` moves them.
+pub struct MonsterItemAI {}
+
+impl<'a> System<'a> for MonsterItemAI {
+	type SystemData = (
+		ReadExpect<'a, Map>,
+		ReadExpect<'a, PlayerData>,
+		ReadExpect<'a, RunState>,
+		Entities<'a>,
+		ReadStorage<'a, Monster>,
+		ReadStorage<'a, Position>,
+		ReadStorage<'a, Viewshed>,
+		ReadStorage<'a, CombatStats>,
+		ReadStorage<'a, Confusion>,
+		ReadStorage<'a, Item>,
+		ReadStorage<'a, InBackpack>,
+		ReadStorage<'a, ProvidesHealing>,
+		ReadStorage<'a, Ranged>,
+		ReadStorage<'a, InflictsDamage>,
+		ReadStorage<'a, AreaOfEffect>,
+		ReadStorage<'a, Charges>,
+		ReadStorage<'a, Equippable>,
+		ReadStorage<'a, Equipped>,
+		ReadStorage<'a, MeleePowerBonus>,
+		ReadStorage<'a, DefenseBonus>,
+		WriteStorage<'a, WantsToUseItem>,
+		WriteStorage<'a, WantsToPickupItem>
+	);
+
+	fn run(&mut self, data: Self::SystemData) {
+		let (
+			map,
+			player_data,
+			run_state,
+			entities,
+			monster,
+			position,
+			viewshed,
+			combat_stats,
+			confusion,
+			items,
+			backpack,
+			healing,
+			ranged,
+			damages,
+			aoe,
+			charges,
+			equippable,
+			equipped,
+			power_bonus,
+			defense_bonus,
+			mut wants_use,
+			mut wants_pickup
+		) = data;
+
+		if *run_state != RunState::MonsterTurn { return; }
+
+		let item_score = |item: Entity| {
+			power_bonus.get(item).map(|b| b.power).unwrap_or(0)
+				+ defense_bonus.get(item).map(|b| b.defense).unwrap_or(0)
+		};
+
+		for (entity, _monster, pos, viewshed, stats) in (&entities, &monster, &position, &viewshed, &combat_stats).join() {
+			if confusion.contains(entity) { continue; }
+
+			let carried = (&entities, &backpack).join()
+				.filter(|(_, pack)| pack.owner == entity)
+				.map(|(item, _)| item)
+				.filter(|item| charges.get(*item).is_none_or(|c| c.current > 0))
+				.collect::<Vec<_>>();
+
+			if stats.hp < stats.max_hp / 2 {
+				if let Some(potion) = carried.iter().find(|item| healing.contains(**item)) {
+					wants_use.insert(entity, WantsToUseItem::new(*potion)).expect("Unable to insert intent");
+					continue;
+				}
+			}
+
+			let monster_pos = Point::new(pos.x, pos.y);
+			let distance = rltk::DistanceAlg::Pythagoras.distance2d(monster_pos, player_data.position);
+			if distance >= 1.5 && viewshed.visible_tiles.contains(&player_data.position) {
+				let attack = carried.iter().find(|item| {
+					(damages.contains(**item) || confusion.contains(**item))
+						&& ranged.get(**item).is_some_and(|r| distance <= r.range as f32)
+						&& aoe.get(**item).is_none_or(|blast| distance > blast.radius as f32)
+				});
+				if let Some(attack) = attack {
+					wants_use.insert(entity, WantsToUseItem::on(*attack, Some(player_data.position))).expect("Unable to insert intent");
+					continue;
+				}
+			}
+
+			let upgrade = carried.iter().find(|item| {
+				let Some(candidate) = equippable.get(**item) else { return false; };
+				let current = (&entities, &equipped).join()
+					.filter(|(_, worn)| worn.owner == entity && worn.slot == candidate.slot)
+					.map(|(worn, _)| item_score(worn))
+					.max();
+				current.is_none_or(|score| item_score(**item) > score)
+			});
+			if let Some(upgrade) = upgrade {
+				wants_use.insert(entity, WantsToUseItem::new(*upgrade)).expect("Unable to insert intent");
+				continue;
+			}
+
+			let idx = map.xy_idx(pos.x, pos.y);
+			if let Some(item) = map.tile_content[idx].iter().find(|e| items.contains(**e) && position.contains(**e)) {
+				wants_pickup.insert(entity, WantsToPickupItem::new(entity, *item)).expect("Unable to insert intent");
+			}
+		}
+	}
+}
@@ -0,0 +1,101 @@
+use std::{fmt::Write as _, fs, time::{SystemTime, UNIX_EPOCH}};
+use specs::{Join, World, WorldExt};
+use crate::{component::*, resource::{gamelog::GameLog, gui::item_label, map::{Map, TileType}, player::PlayerEntity, run_stats::RunStats}};
+
+const MORGUE_DIR: &str = "./morgue";
+
+/// Writes a plain-text account of the finished run to the morgue directory.
+pub fn write_morgue(ecs: &World) {
+	let report = morgue_report(ecs);
+	let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or_default();
+	if fs::create_dir_all(MORGUE_DIR).is_err() {
+		return;
+	}
+	let _ = fs::write(format!("{}/morgue-{}.txt", MORGUE_DIR, timestamp), report);
+}
+
+fn morgue_report(ecs: &World) -> String {
+	let player = ecs.fetch::<PlayerEntity>().0;
+	let stats = ecs.fetch::<RunStats>();
+	let map = ecs.fetch::<Map>();
+	let log = ecs.fetch::<GameLog>();
+	let names = ecs.read_storage::<Name>();
+	let combat_stats = ecs.read_storage::<CombatStats>();
+	let purses = ecs.read_storage::<Purse>();
+	let backpack = ecs.read_storage::<InBackpack>();
+	let equipped = ecs.read_storage::<Equipped>();
+	let stacks = ecs.read_storage::<Stackable>();
+	let charges = ecs.read_storage::<Charges>();
+	let entities = ecs.entities();
+
+	let mut out = String::new();
+	let _ = writeln!(out, "Rust Roguelike Tutorial - morgue file");
+	let _ = writeln!(out);
+	if stats.victory {
+		let _ = writeln!(out, "Escaped with the Amulet of the Ancients.");
+	} else {
+		let cause = stats.cause_of_death.clone().unwrap_or_else(|| "unknown forces".to_string());
+		let _ = writeln!(out, "Killed by {} on depth {}.", cause, map.depth);
+	}
+	let _ = writeln!(out, "Score: {}", score(&stats, map.depth));
+	let _ = writeln!(out);
+
+	let _ = writeln!(out, "== Character ==");
+	if let Some(hp) = combat_stats.get(player) {
+		let _ = writeln!(out, "HP: {}/{}  Power: {}  Defense: {}", hp.hp, hp.max_hp, hp.power, hp.defense);
+	}
+	if let Some(purse) = purses.get(player) {
+		let _ = writeln!(out, "Gold: {}", purse.gold);
+	}
+	let _ = writeln!(out, "Depth: {}  Turns: {}  Items used: {}", map.depth, stats.turns, stats.items_used);
+	let _ = writeln!(out);
+
+	let _ = writeln!(out, "== Equipment ==");
+	for (_, worn, name) in (&entities, &equipped, &names).join().filter(|(_, worn, _)| worn.owner == player) {
+		let _ = writeln!(out, "{:?}: {}", worn.slot, name.name);
+	}
+	let _ = writeln!(out);
+
+	let _ = writeln!(out, "== Inventory ==");
+	for (item, _, name) in (&entities, &backpack, &names).join().filter(|(_, pack, _)| pack.owner == player) {
+		let _ = writeln!(out, "{}", item_label(name, stacks.get(item), charges.get(item)));
+	}
+	let _ = writeln!(out);
+
+	let _ = writeln!(out, "== Kills ({}) ==", stats.total_kills());
+	for (name, count) in stats.kills.iter() {
+		let _ = writeln!(out, "{} x{}", name, count);
+	}
+	let _ = writeln!(out);
+
+	let _ = writeln!(out, "== Last messages ==");
+	let recent = log.entries.iter().rev().take(10).collect::<Vec<_>>();
+	for entry in recent.iter().rev() {
+		let _ = writeln!(out, "[{:>5}] {}", entry.turn, entry);
+	}
+	let _ = writeln!(out);
+
+	let _ = writeln!(out, "== Map ==");
+	for y in 0..map.height {
+		let row = (0..map.width).map(|x| {
+			let idx = map.xy_idx(x, y);
+			if !map.revealed_tiles[idx] {
+				return ' ';
+			}
+			match map.tiles[idx] {
+				TileType::Floor => '.',
+				TileType::Wall => '#',
+				TileType::DownStairs => '>',
+			}
+		}).collect::<String>();
+		let _ = writeln!(out, "{}", row.trim_end());
+	}
+
+	out
+}
+
+/// Deeper runs score more; kills add a little and winning trumps everything.
+pub fn score(stats: &RunStats, depth: i32) -> i32 {
+	let victory_bonus = if stats.victory { 5000 } else { 0 };
+	depth * 100 + stats.total_kills() * 10 + victory_bonus
+}
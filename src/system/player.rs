@@ -1,55 +1,95 @@
-use rltk::{console, Rltk, VirtualKeyCode};
+use rltk::{console, Point, RandomNumberGenerator, Rltk, VirtualKeyCode};
 use specs::{storage::GenericReadStorage, Entity, Join, World, WorldExt};
-use std::cmp;
 
 use crate::{
     component::*,
     resource::{
+        camera::Camera,
         gamelog::GameLog,
+        gui::{overlay_for_key, InventoryView, VendorMode},
         map::{Map, TileType},
-        player::{PlayerData, PlayerEntity},
+        overlay::DebugOverlays,
+        palette,
+        player::{AutoWalk, PlayerData, PlayerEntity},
+        settings::SettingsOption,
+        wizard::WizardMode,
     },
     state::{RunState, State},
+    system::replay,
 };
 
 pub fn player_input(gs: &mut State, ctx: &mut Rltk) -> RunState {
     // Player movement
     match ctx.key {
         None => {
-            return RunState::AwaitingInput;
-        } // Nothing happened
+            return mouse_input(gs, ctx);
+        }
         Some(key) => match key {
             VirtualKeyCode::Left | VirtualKeyCode::Numpad4 | VirtualKeyCode::H => {
-                try_move_player(-1, 0, &mut gs.ecs)
+                return try_move_player(-1, 0, &mut gs.ecs);
             }
 
             VirtualKeyCode::Right | VirtualKeyCode::Numpad6 | VirtualKeyCode::L => {
-                try_move_player(1, 0, &mut gs.ecs)
+                return try_move_player(1, 0, &mut gs.ecs);
             }
 
             VirtualKeyCode::Up | VirtualKeyCode::Numpad8 | VirtualKeyCode::K => {
-                try_move_player(0, -1, &mut gs.ecs)
+                return try_move_player(0, -1, &mut gs.ecs);
             }
 
             VirtualKeyCode::Down | VirtualKeyCode::Numpad2 | VirtualKeyCode::J => {
-                try_move_player(0, 1, &mut gs.ecs)
+                return try_move_player(0, 1, &mut gs.ecs);
             }
 
-            VirtualKeyCode::Numpad9 | VirtualKeyCode::Z => try_move_player(1, -1, &mut gs.ecs),
+            VirtualKeyCode::Numpad9 | VirtualKeyCode::Z => return try_move_player(1, -1, &mut gs.ecs),
 
-            VirtualKeyCode::Numpad7 | VirtualKeyCode::U => try_move_player(-1, -1, &mut gs.ecs),
+            VirtualKeyCode::Numpad7 | VirtualKeyCode::U => return try_move_player(-1, -1, &mut gs.ecs),
 
-            VirtualKeyCode::Numpad3 | VirtualKeyCode::N => try_move_player(1, 1, &mut gs.ecs),
+            VirtualKeyCode::Numpad3 | VirtualKeyCode::N => return try_move_player(1, 1, &mut gs.ecs),
 
-            VirtualKeyCode::Numpad1 | VirtualKeyCode::B => try_move_player(-1, 1, &mut gs.ecs),
+            VirtualKeyCode::Numpad1 | VirtualKeyCode::B => return try_move_player(-1, 1, &mut gs.ecs),
 
             VirtualKeyCode::G => get_item(&mut gs.ecs),
-            VirtualKeyCode::I => return RunState::ShowInventory,
-            VirtualKeyCode::D => return RunState::ShowDropItem,
+            VirtualKeyCode::I => {
+                return RunState::ShowInventory {
+                    view: InventoryView::default(),
+                }
+            }
+            VirtualKeyCode::D => {
+                return RunState::ShowDropItem {
+                    view: InventoryView::default(),
+                }
+            }
+            VirtualKeyCode::C => return RunState::ShowCharacter,
+            VirtualKeyCode::M => return RunState::ShowMessageLog { offset: 0 },
+            VirtualKeyCode::X => {
+                let cursor = gs.ecs.fetch::<PlayerData>().position;
+                return RunState::LookCursor { cursor };
+            }
             VirtualKeyCode::Period if try_next_level(&mut gs.ecs) => return RunState::NextLevel,
 
             VirtualKeyCode::Space | VirtualKeyCode::Numpad5 => return skip_turn(&mut gs.ecs),
 
+            VirtualKeyCode::Grave if gs.ecs.fetch::<WizardMode>().enabled => {
+                return RunState::ShowWizardMenu;
+            }
+
+            key if overlay_for_key(key).is_some() && gs.ecs.fetch::<WizardMode>().enabled => {
+                let overlay = overlay_for_key(key).expect("Overlay hotkey without an overlay");
+                gs.ecs.fetch_mut::<DebugOverlays>().toggle(overlay);
+                return RunState::AwaitingInput;
+            }
+
+            VirtualKeyCode::F10 => {
+                gs.settings.change(SettingsOption::Palette, true);
+                gs.settings.save();
+                palette::set_palette(gs.settings.palette);
+                gs.ecs
+                    .fetch_mut::<GameLog>()
+                    .log(format!("Colour palette: {}.", gs.settings.palette.label()));
+                return RunState::AwaitingInput;
+            }
+
             VirtualKeyCode::Escape => return RunState::SaveGame,
             _ => return RunState::AwaitingInput,
         },
@@ -57,25 +97,162 @@ pub fn player_input(gs: &mut State, ctx: &mut Rltk) -> RunState {
     RunState::PlayerTurn
 }
 
-fn skip_turn(ecs: &mut World) -> RunState {
-    let player_entity = ecs.fetch::<PlayerEntity>().0;
-    let viewshed = ecs.read_storage::<Viewshed>();
-    let monsters = ecs.read_storage::<Monster>();
+/// Left-clicking the map attacks an adjacent creature or walks to the clicked tile;
+/// right-clicking an item opens a menu of what to do with it.
+fn mouse_input(gs: &mut State, ctx: &mut Rltk) -> RunState {
+    if !ctx.left_click && !gs.right_click {
+        return RunState::AwaitingInput;
+    }
+    let (mouse_x, mouse_y) = ctx.mouse_pos();
+    let Some(target) = Camera::new(&gs.ecs).to_world(Point::new(mouse_x, mouse_y)) else {
+        return RunState::AwaitingInput;
+    };
+    let map = gs.ecs.fetch::<Map>();
+    if !map.in_bounds(target.x, target.y) {
+        return RunState::AwaitingInput;
+    }
+    let idx = map.xy_idx(target.x, target.y);
 
-    let worldmap_resource = ecs.fetch::<Map>();
+    if gs.right_click {
+        if !map.visible_tiles[idx] {
+            return RunState::AwaitingInput;
+        }
+        let items = gs.ecs.read_storage::<Item>();
+        return match map.tile_content[idx].iter().find(|entity| items.contains(**entity)) {
+            Some(item) => RunState::ItemContext {
+                item: *item,
+                selection: 0,
+            },
+            None => RunState::AwaitingInput,
+        };
+    }
 
-    let mut can_heal = true;
-    let viewshed = viewshed.get(player_entity).unwrap();
-    for tile in viewshed.visible_tiles.iter() {
-        let idx = worldmap_resource.xy_idx(tile.x, tile.y);
-        for entity in worldmap_resource.tile_content[idx].iter() {
-            if let Some(_) = monsters.get(*entity) {
-                can_heal = false;
-                break;
+    let player = gs.ecs.fetch::<PlayerData>().position;
+    let (delta_x, delta_y) = (target.x - player.x, target.y - player.y);
+    let combat_stats = gs.ecs.read_storage::<CombatStats>();
+    let adjacent = delta_x.abs() <= 1 && delta_y.abs() <= 1 && (delta_x, delta_y) != (0, 0);
+    if adjacent && map.tile_content[idx].iter().any(|entity| combat_stats.contains(*entity)) {
+        drop(map);
+        drop(combat_stats);
+        return try_move_player(delta_x, delta_y, &mut gs.ecs);
+    }
+    if !map.revealed_tiles[idx] || map.tiles[idx] == TileType::Wall || target == player {
+        return RunState::AwaitingInput;
+    }
+    drop(map);
+    drop(combat_stats);
+    start_walk(&mut gs.ecs, target, None)
+}
+
+/// Sets the player walking to `target`, if there is a way there, and picking up `pickup`
+/// once they arrive.
+pub fn start_walk(ecs: &mut World, target: Point, pickup: Option<Entity>) -> RunState {
+    let player = ecs.fetch::<PlayerData>().position;
+    let path = {
+        let map = ecs.fetch::<Map>();
+        rltk::a_star_search(map.xy_idx(player.x, player.y), map.xy_idx(target.x, target.y), &*map)
+    };
+    if !path.success {
+        ecs.fetch_mut::<GameLog>().log("You cannot find a way there.".to_string());
+        return RunState::AwaitingInput;
+    }
+    let monsters_in_view = monsters_in_view(ecs);
+    let hp = player_hp(ecs);
+    ecs.fetch_mut::<PlayerData>().walk = Some(AutoWalk {
+        target,
+        monsters_in_view,
+        hp,
+        steps: 0,
+        pickup,
+    });
+    RunState::AutoWalk
+}
+
+/// Takes the next step of a walk started by clicking on the map. The path is worked out
+/// afresh each step, and the walk stops on arrival, when the way is blocked, when the
+/// player is hurt, when a monster they had not seen comes into view or on any input.
+pub fn auto_walk(gs: &mut State, ctx: &mut Rltk) -> RunState {
+    let ecs = &mut gs.ecs;
+    let Some(walk) = ecs.fetch::<PlayerData>().walk.clone() else {
+        return RunState::AwaitingInput;
+    };
+    if ctx.key.is_some_and(replay::records_key) || ctx.left_click || gs.right_click {
+        return stop_walking(ecs);
+    }
+    let player = ecs.fetch::<PlayerData>().position;
+    let hp = player_hp(ecs);
+    if hp < walk.hp {
+        ecs.fetch_mut::<GameLog>().log("You stop: you are hurt.".to_string());
+        return stop_walking(ecs);
+    }
+    let monsters_in_view = monsters_in_view(ecs);
+    if monsters_in_view.iter().any(|monster| !walk.monsters_in_view.contains(monster)) {
+        ecs.fetch_mut::<GameLog>().log("You stop: something has come into view.".to_string());
+        return stop_walking(ecs);
+    }
+    if player == walk.target {
+        stop_walking(ecs);
+        let positions = ecs.read_storage::<Position>();
+        return match walk.pickup {
+            Some(item) if positions.get(item).is_some_and(|pos| pos.x == player.x && pos.y == player.y) => {
+                drop(positions);
+                pick_up(ecs, item);
+                RunState::PlayerTurn
             }
-        }
+            _ => RunState::AwaitingInput,
+        };
     }
 
+    let (path, width) = {
+        let map = ecs.fetch::<Map>();
+        let path = rltk::a_star_search(map.xy_idx(player.x, player.y), map.xy_idx(walk.target.x, walk.target.y), &*map);
+        (path, map.width)
+    };
+    if !path.success || path.steps.len() < 2 {
+        return stop_walking(ecs);
+    }
+    let next = path.steps[1] as i32;
+    ecs.fetch_mut::<PlayerData>().walk = Some(AutoWalk {
+        monsters_in_view,
+        hp,
+        steps: walk.steps + 1,
+        ..walk
+    });
+    try_move_player(next % width - player.x, next / width - player.y, ecs)
+}
+
+fn stop_walking(ecs: &mut World) -> RunState {
+    ecs.fetch_mut::<PlayerData>().walk = None;
+    RunState::AwaitingInput
+}
+
+fn player_hp(ecs: &World) -> i32 {
+    let player_entity = ecs.fetch::<PlayerEntity>().0;
+    ecs.read_storage::<CombatStats>().get(player_entity).map(|stats| stats.hp).unwrap_or_default()
+}
+
+/// The monsters the player can currently see.
+fn monsters_in_view(ecs: &World) -> Vec<Entity> {
+    let player_entity = ecs.fetch::<PlayerEntity>().0;
+    let viewsheds = ecs.read_storage::<Viewshed>();
+    let monsters = ecs.read_storage::<Monster>();
+    let map = ecs.fetch::<Map>();
+    let Some(viewshed) = viewsheds.get(player_entity) else {
+        return Vec::new();
+    };
+    viewshed
+        .visible_tiles
+        .iter()
+        .map(|tile| map.xy_idx(tile.x, tile.y))
+        .flat_map(|idx| map.tile_content[idx].iter().copied())
+        .filter(|entity| monsters.contains(*entity))
+        .collect()
+}
+
+fn skip_turn(ecs: &mut World) -> RunState {
+    let player_entity = ecs.fetch::<PlayerEntity>().0;
+    let can_heal = monsters_in_view(ecs).is_empty();
+
     if can_heal {
         let mut healths = ecs.write_storage::<CombatStats>();
         if let Some(health) = healths.get_mut(player_entity) {
@@ -99,6 +276,14 @@ fn try_next_level(ecs: &mut World) -> bool {
     }
 }
 
+/// Has the player pick up `item` this turn.
+pub fn pick_up(ecs: &mut World, item: Entity) {
+    let player_entity = ecs.fetch::<PlayerEntity>().0;
+    ecs.write_storage::<WantsToPickupItem>()
+        .insert(player_entity, WantsToPickupItem::new(player_entity, item))
+        .expect("Unable to start item pickup");
+}
+
 fn get_item(ecs: &mut World) {
     let player_data = ecs.fetch::<PlayerData>();
     let player_entity = ecs.fetch::<PlayerEntity>();
@@ -116,9 +301,7 @@ fn get_item(ecs: &mut World) {
     }
 
     match target_item {
-        None => gamelog
-            .entries
-            .push("There is nothing here to pickup".to_string()),
+        None => gamelog.log("There is nothing here to pickup".to_string()),
         Some(item) => {
             let mut pickup = ecs.write_storage::<WantsToPickupItem>();
             pickup
@@ -131,13 +314,44 @@ fn get_item(ecs: &mut World) {
     }
 }
 
-fn try_move_player(delta_x: i32, delta_y: i32, ecs: &mut World) {
+/// A confused player staggers in a random direction instead of the one they chose.
+fn stagger(delta_x: i32, delta_y: i32, ecs: &mut World) -> (i32, i32) {
+    let player_entity = ecs.fetch::<PlayerEntity>().0;
+    if !ecs.read_storage::<Confusion>().contains(player_entity) {
+        return (delta_x, delta_y);
+    }
+    let mut rng = ecs.write_resource::<RandomNumberGenerator>();
+    loop {
+        let delta = (rng.range(-1, 2), rng.range(-1, 2));
+        if delta != (0, 0) {
+            return delta;
+        }
+    }
+}
+
+/// Counts down the player's confusion by one turn, lifting it once it runs out.
+pub fn wear_off_confusion(ecs: &mut World) {
+    let player_entity = ecs.fetch::<PlayerEntity>().0;
+    let mut confusion = ecs.write_storage::<Confusion>();
+    let Some(confused) = confusion.get_mut(player_entity) else {
+        return;
+    };
+    confused.turns -= 1;
+    if confused.turns < 1 {
+        confusion.remove(player_entity);
+        ecs.fetch_mut::<GameLog>().log("You are no longer confused.".to_string());
+    }
+}
+
+fn try_move_player(delta_x: i32, delta_y: i32, ecs: &mut World) -> RunState {
+    let (delta_x, delta_y) = stagger(delta_x, delta_y, ecs);
     let mut positions = ecs.write_storage::<Position>();
     let mut players = ecs.write_storage::<Player>();
     let mut viewsheds = ecs.write_storage::<Viewshed>();
     let combat_stats = ecs.read_storage::<CombatStats>();
     let entities = ecs.entities();
     let mut wants_to_melee = ecs.write_storage::<WantsToMelee>();
+    let vendors = ecs.read_storage::<Vendor>();
 
     let map = ecs.fetch::<Map>();
 
@@ -147,6 +361,13 @@ fn try_move_player(delta_x: i32, delta_y: i32, ecs: &mut World) {
         let destination_idx = map.xy_idx(pos.x + delta_x, pos.y + delta_y);
 
         for potential_target in map.tile_content[destination_idx].iter() {
+            if vendors.contains(*potential_target) {
+                return RunState::ShowVendor {
+                    vendor: *potential_target,
+                    mode: VendorMode::Buy,
+                    page: 0,
+                };
+            }
             if let Some(target) = combat_stats.get(*potential_target) {
                 wants_to_melee
                     .insert(
@@ -156,15 +377,15 @@ fn try_move_player(delta_x: i32, delta_y: i32, ecs: &mut World) {
                         },
                     )
                     .expect("Add target failed");
-                return;
+                return RunState::PlayerTurn;
             }
         }
 
         if map.blocked[destination_idx] {
             continue;
         }
-        pos.x = cmp::min(79, cmp::max(0, pos.x + delta_x));
-        pos.y = cmp::min(49, cmp::max(0, pos.y + delta_y));
+        pos.x = (pos.x + delta_x).clamp(0, map.width - 1);
+        pos.y = (pos.y + delta_y).clamp(0, map.height - 1);
 
         let mut player_data = ecs.write_resource::<PlayerData>();
         player_data.position.x = pos.x;
@@ -172,4 +393,5 @@ fn try_move_player(delta_x: i32, delta_y: i32, ecs: &mut World) {
 
         viewshed.dirty = true;
     }
+    RunState::PlayerTurn
 }
@@ -0,0 +1,217 @@
+use std::{collections::hash_map::DefaultHasher, fs::{self, OpenOptions}, hash::{Hash, Hasher}, io::Write, time::{SystemTime, UNIX_EPOCH}};
+use rltk::VirtualKeyCode;
+use serde::{Deserialize, Serialize};
+use specs::{Join, World, WorldExt};
+use crate::{component::{CombatStats, Position}, resource::{map::Map, run_stats::RunStats}};
+
+const REPLAY_DIR: &str = "./replays";
+/// Delay between two replayed inputs, so playback can be followed by eye.
+pub const PLAYBACK_DELAY_MS: f32 = 80.0;
+
+/// Every key the game reacts to; anything else is a no-op and is not recorded.
+const REPLAY_KEYS: [VirtualKeyCode; 52] = [
+	VirtualKeyCode::A, VirtualKeyCode::B, VirtualKeyCode::C, VirtualKeyCode::D, VirtualKeyCode::E,
+	VirtualKeyCode::F, VirtualKeyCode::G, VirtualKeyCode::H, VirtualKeyCode::I, VirtualKeyCode::J,
+	VirtualKeyCode::K, VirtualKeyCode::L, VirtualKeyCode::M, VirtualKeyCode::N, VirtualKeyCode::O,
+	VirtualKeyCode::P, VirtualKeyCode::Q, VirtualKeyCode::R, VirtualKeyCode::S, VirtualKeyCode::T,
+	VirtualKeyCode::U, VirtualKeyCode::V, VirtualKeyCode::W, VirtualKeyCode::X, VirtualKeyCode::Y,
+	VirtualKeyCode::Z,
+	VirtualKeyCode::Numpad1, VirtualKeyCode::Numpad2, VirtualKeyCode::Numpad3, VirtualKeyCode::Numpad4,
+	VirtualKeyCode::Numpad5, VirtualKeyCode::Numpad6, VirtualKeyCode::Numpad7, VirtualKeyCode::Numpad8,
+	VirtualKeyCode::Numpad9,
+	VirtualKeyCode::Left, VirtualKeyCode::Right, VirtualKeyCode::Up, VirtualKeyCode::Down,
+	VirtualKeyCode::PageUp, VirtualKeyCode::PageDown, VirtualKeyCode::Home, VirtualKeyCode::End,
+	VirtualKeyCode::Escape, VirtualKeyCode::Return, VirtualKeyCode::Space, VirtualKeyCode::Tab,
+	VirtualKeyCode::Period, VirtualKeyCode::Comma, VirtualKeyCode::Slash, VirtualKeyCode::Back,
+	VirtualKeyCode::Grave,
+];
+
+/// Whether a key is one that replays record, and so one that may change the game.
+pub fn records_key(key: VirtualKeyCode) -> bool {
+	REPLAY_KEYS.contains(&key)
+}
+
+fn key_name(key: VirtualKeyCode) -> Option<String> {
+	records_key(key).then(|| format!("{:?}", key))
+}
+
+fn key_from_name(name: &str) -> Option<VirtualKeyCode> {
+	REPLAY_KEYS.iter().copied().find(|key| format!("{:?}", key) == name)
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ReplayInput {
+	pub key: Option<String>,
+	pub shift: bool,
+	pub left_click: bool,
+	/// Missing from replays recorded before right-clicks did anything.
+	#[serde(default)]
+	pub right_click: bool,
+	pub mouse_pos: (i32, i32),
+	/// For input that cut a click-to-move walk short, how many steps into the walk it came.
+	#[serde(default)]
+	pub walk_step: Option<u32>,
+}
+
+/// One line of a replay file. Replays are written a line at a time while the game is played,
+/// so a run that crashes or is quit part way still leaves its replay behind.
+#[derive(Serialize, Deserialize)]
+enum ReplayLine {
+	Start { seed: u64, autosave: bool, save: Option<String> },
+	Input(ReplayInput),
+	Finish { final_hash: u64 },
+}
+
+/// The seed of a game plus every input the player gave while the game was waiting for one.
+/// A game resumed from a save also carries the save it was resumed from.
+#[derive(Clone, Default)]
+pub struct Replay {
+	pub seed: u64,
+	pub save: Option<String>,
+	pub inputs: Vec<ReplayInput>,
+	pub final_hash: Option<u64>,
+	/// Whether the game saved on every new level, which playback has to repeat.
+	pub autosave: bool,
+	/// The file being recorded to, which is only created once there is input to write.
+	path: Option<String>,
+	started: bool,
+}
+
+impl Replay {
+	pub fn new(seed: u64, autosave: bool, save: Option<String>) -> Self {
+		let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis()).unwrap_or_default();
+		let path = Some(format!("{}/replay-{}.jsonl", REPLAY_DIR, timestamp));
+		Self { seed, save, autosave, path, ..Self::default() }
+	}
+
+	/// A replay that records nothing, for wizard games and for playback itself.
+	pub fn disabled() -> Self {
+		Self::default()
+	}
+
+	/// Records this frame's input. The terminal only reports left-clicks, so whether the right
+	/// button went down this frame is passed in, as is the step of any walk under way.
+	pub fn record(&mut self, ctx: &rltk::Rltk, right_click: bool, walk_step: Option<u32>) {
+		if self.path.is_none() { return; }
+		let key = ctx.key.and_then(key_name);
+		if key.is_none() && !ctx.left_click && !right_click { return; }
+		let input = ReplayInput { key, shift: ctx.shift, left_click: ctx.left_click, right_click, mouse_pos: ctx.mouse_pos, walk_step };
+		if !self.started {
+			self.started = true;
+			self.write(&ReplayLine::Start { seed: self.seed, autosave: self.autosave, save: self.save.clone() });
+		}
+		self.write(&ReplayLine::Input(input));
+	}
+
+	/// Closes the replay of a finished run with a fingerprint of the state it ended in.
+	pub fn finish(&mut self, ecs: &World) {
+		if !self.started { return; }
+		self.write(&ReplayLine::Finish { final_hash: state_hash(ecs) });
+		self.path = None;
+	}
+
+	/// Appends a line to the replay file. A replay that cannot be written stops recording.
+	fn write(&mut self, line: &ReplayLine) {
+		let Some(path) = &self.path else { return; };
+		let written = fs::create_dir_all(REPLAY_DIR).ok()
+			.and_then(|_| serde_json::to_string(line).ok())
+			.and_then(|data| {
+				let mut file = OpenOptions::new().create(true).append(true).open(path).ok()?;
+				writeln!(file, "{}", data).ok()
+			});
+		if written.is_none() {
+			self.path = None;
+		}
+	}
+
+	pub fn load(path: &str) -> Option<Self> {
+		let data = fs::read_to_string(path).ok()?;
+		let mut lines = data.lines().map(serde_json::from_str::<ReplayLine>);
+		let Some(Ok(ReplayLine::Start { seed, autosave, save })) = lines.next() else {
+			return None;
+		};
+		let mut replay = Self { seed, save, autosave, ..Self::default() };
+		for line in lines {
+			match line {
+				Ok(ReplayLine::Input(input)) => replay.inputs.push(input),
+				Ok(ReplayLine::Finish { final_hash }) => replay.final_hash = Some(final_hash),
+				// A crash can leave the last line half written.
+				Ok(ReplayLine::Start { .. }) | Err(_) => break,
+			}
+		}
+		Some(replay)
+	}
+}
+
+/// Feeds a recorded replay back into the game in place of the player's input.
+pub struct Playback {
+	pub replay: Replay,
+	pub cursor: usize,
+	elapsed: f32,
+}
+
+impl Playback {
+	pub fn new(replay: Replay) -> Self {
+		Self { replay, cursor: 0, elapsed: 0.0 }
+	}
+
+	pub fn finished(&self) -> bool {
+		self.cursor >= self.replay.inputs.len()
+	}
+
+	/// Replaces this frame's input with the next recorded one, or with nothing while waiting.
+	/// Returns whether the recorded input was a right-click.
+	///
+	/// During a walk the next input only comes in on the step it interrupted when recorded,
+	/// with no delay, since the walk carries on in the meantime.
+	pub fn feed(&mut self, ctx: &mut rltk::Rltk, walk_step: Option<u32>) -> bool {
+		ctx.key = None;
+		ctx.left_click = false;
+		ctx.shift = false;
+
+		if self.finished() { return false; }
+		if walk_step.is_some() {
+			if self.replay.inputs[self.cursor].walk_step != walk_step { return false; }
+		} else {
+			self.elapsed += ctx.frame_time_ms;
+			if self.elapsed < PLAYBACK_DELAY_MS { return false; }
+		}
+		self.elapsed = 0.0;
+
+		let input = &self.replay.inputs[self.cursor];
+		self.cursor += 1;
+		ctx.key = input.key.as_deref().and_then(key_from_name);
+		ctx.shift = input.shift;
+		ctx.left_click = input.left_click;
+		ctx.mouse_pos = input.mouse_pos;
+		input.right_click
+	}
+}
+
+/// Fingerprint of the game state, used to check a replay ends where it did when recorded.
+pub fn state_hash(ecs: &World) -> u64 {
+	let mut hasher = DefaultHasher::new();
+	let map = ecs.fetch::<Map>();
+	map.depth.hash(&mut hasher);
+	for tile in map.tiles.iter() {
+		(*tile as u8).hash(&mut hasher);
+	}
+	map.revealed_tiles.hash(&mut hasher);
+
+	let stats = ecs.fetch::<RunStats>();
+	stats.turns.hash(&mut hasher);
+	stats.items_used.hash(&mut hasher);
+	stats.kills.hash(&mut hasher);
+
+	let entities = ecs.entities();
+	let positions = ecs.read_storage::<Position>();
+	let combat_stats = ecs.read_storage::<CombatStats>();
+	for (entity, pos) in (&entities, &positions).join() {
+		entity.id().hash(&mut hasher);
+		(pos.x, pos.y).hash(&mut hasher);
+		if let Some(hp) = combat_stats.get(entity) {
+			hp.hp.hash(&mut hasher);
+		}
+	}
+	hasher.finish()
+}
@@ -1,6 +1,6 @@
 use std::{fs::{self, File}, path::Path};
 use specs::{saveload::{MarkedBuilder, SimpleMarker, SimpleMarkerAllocator}, Builder, Entity, Join, World, WorldExt};
-use crate::{component::*, resource::{map::{self, Map}, player::{PlayerData, PlayerEntity}}};
+use crate::{component::*, resource::{map::{self, Map}, player::{PlayerData, PlayerEntity}, run_stats::RunStats}};
 #[allow(deprecated)]
 use specs::error::NoError;
 use specs::saveload::{SerializeComponents, DeserializeComponents};
@@ -39,14 +39,25 @@ macro_rules! deserialize_individually {
 }
 
 pub fn save_game(ecs: &mut World) {
+	save_world(ecs, true);
+}
+
+/// Goes through the motions of a save without touching the save file. A save creates and
+/// deletes an entity, so replays that saved when recorded do this to keep entity ids in step.
+pub fn rehearse_save(ecs: &mut World) {
+	save_world(ecs, false);
+}
+
+fn save_world(ecs: &mut World, write: bool) {
 	let mapcopy = ecs.get_mut::<map::Map>().unwrap().clone();
+	let statscopy = ecs.get_mut::<RunStats>().unwrap().clone();
 	let savehelper = ecs
 		.create_entity()
-		.with(SerializationHelper { map: mapcopy })
+		.with(SerializationHelper { map: mapcopy, stats: statscopy })
 		.marked::<SimpleMarker<SerializeMe>>()
 		.build();
 
-	{
+	if write {
 		let data = ( ecs.entities(), ecs.read_storage::<SimpleMarker<SerializeMe>>() );
 
 		let writer = File::create(SAVE_FILE_NAME).unwrap();
@@ -55,7 +66,8 @@ pub fn save_game(ecs: &mut World) {
 			Position, Renderable, Player, Viewshed, Monster, 
 			Name, BlocksTile, CombatStats, SufferDamage, WantsToMelee, Item, Consumable, Ranged, InflictsDamage, 
 			AreaOfEffect, Confusion, ProvidesHealing, InBackpack, WantsToPickupItem, WantsToUseItem,
-			WantsToDropItem, SerializationHelper);
+			WantsToDropItem, WantsToThrowItem, Charges, Recharge, Stackable, Weight, Purse, Gold, Value, Vendor, LootTable, LeavesCorpse, Decays,
+			Equippable, Equipped, MeleePowerBonus, DefenseBonus, Boss, QuestItem, Description, SerializationHelper);
 	}
 
 	ecs.delete_entity(savehelper).expect("Failed to cleanup during save");
@@ -65,11 +77,15 @@ pub fn has_save_game() -> bool {
 	Path::new(SAVE_FILE_NAME).exists()
 }
 
-pub fn load_game(ecs: &mut World) {
+pub fn read_save() -> String {
+	fs::read_to_string(SAVE_FILE_NAME).unwrap()
+}
+
+/// Replaces every entity in the world with the ones in `data`, as read by `read_save`.
+pub fn load_game(ecs: &mut World, data: &str) {
 	ecs.delete_all();
 
-	let data = fs::read_to_string(SAVE_FILE_NAME).unwrap();
-	let mut de = serde_json::Deserializer::from_str(&data);
+	let mut de = serde_json::Deserializer::from_str(data);
 	{
 		let mut d = (
 			&mut ecs.entities(), 
@@ -79,7 +95,8 @@ pub fn load_game(ecs: &mut World) {
 		deserialize_individually!(ecs, de, d, Position, Renderable, Player, Viewshed, Monster, 
 			Name, BlocksTile, CombatStats, SufferDamage, WantsToMelee, Item, Consumable, Ranged, InflictsDamage, 
 			AreaOfEffect, Confusion, ProvidesHealing, InBackpack, WantsToPickupItem, WantsToUseItem,
-			WantsToDropItem, SerializationHelper
+			WantsToDropItem, WantsToThrowItem, Charges, Recharge, Stackable, Weight, Purse, Gold, Value, Vendor, LootTable, LeavesCorpse, Decays,
+			Equippable, Equipped, MeleePowerBonus, DefenseBonus, Boss, QuestItem, Description, SerializationHelper
 		);
 	}
 
@@ -92,7 +109,9 @@ pub fn load_game(ecs: &mut World) {
 		for (e, h) in (&entities, &helper).join() {
 			let mut worldmap = ecs.write_resource::<Map>();
 			*worldmap = h.map.clone();
-			worldmap.tile_content = vec![Vec::new(); map::SIZE];
+			worldmap.tile_content = vec![Vec::new(); (worldmap.width * worldmap.height) as usize];
+			worldmap.refresh_wall_masks();
+			*ecs.write_resource::<RunStats>() = h.stats.clone();
 			deleteme = Some(e);
 		}
 		for (e, _p, pos) in (&entities, &player, &position).join() {
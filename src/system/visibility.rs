@@ -28,7 +28,7 @@ impl<'a> System<'a> for VisibilitySystem {
 			viewshed.dirty = false;
 			viewshed.visible_tiles.clear();
 			viewshed.visible_tiles = field_of_view(Point::new(pos.x, pos.y), viewshed.range, &*map);
-			viewshed.visible_tiles.retain(|p| p.x >= 0 && p.x < map.width && p.y >= 0 && p.y < map.height);
+			viewshed.visible_tiles.retain(|p| map.in_bounds(p.x, p.y));
 
 			if let Some(_player) = player.get(ent) {
 				for t in map.visible_tiles.iter_mut() {
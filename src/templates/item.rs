@@ -1,7 +1,7 @@
-use rltk::{RandomNumberGenerator, RGB};
-use specs::{saveload::{MarkedBuilder, SimpleMarker}, Builder, World, WorldExt};
+use rltk::RandomNumberGenerator;
+use specs::{saveload::{MarkedBuilder, SimpleMarker}, Builder, Entity, World, WorldExt};
 
-use crate::component::*;
+use crate::{component::*, resource::{map::Map, palette::Color}};
 
 use super::render_order;
 
@@ -11,14 +11,18 @@ pub fn health_potion(ecs: &mut World, x: i32, y: i32) {
 		.with(Position::new(x, y))
 		.with(Renderable {
 			glyph: rltk::to_cp437('¡'),
-			fg: RGB::named(rltk::MAGENTA),
-			bg: RGB::named(rltk::BLACK),
+			fg: Color::Healing,
+			bg: Color::Background,
 			render_order: render_order::ITEM,
 		})
 		.with(Name::new("Health Potion".to_string()))
+		.with(Description::new("A small flask of red liquid that knits flesh back together."))
 		.with(Item {})
+		.with(Weight { weight: 1.0 })
+		.with(Value { gold: 20 })
 		.with(ProvidesHealing { heal_amount: 8 })
 		.with(Consumable {})
+		.with(Stackable::new(1))
 		.build();
 }
 
@@ -28,13 +32,17 @@ pub fn magic_missile_scroll(ecs: &mut World, x: i32, y: i32) {
 		.with(Position::new(x, y))
 		.with(Renderable {
 			glyph: rltk::to_cp437(')'),
-			fg: RGB::named(rltk::CYAN),
-			bg: RGB::named(rltk::BLACK),
+			fg: Color::Magic,
+			bg: Color::Background,
 			render_order: render_order::ITEM,
 		})
 		.with(Name::new("Magic Missile Scroll".to_string()))
+		.with(Description::new("A scroll inscribed with a single word of force. Reading it hurls a bolt at a nearby foe."))
 		.with(Item {})
+		.with(Weight { weight: 0.5 })
+		.with(Value { gold: 30 })
 		.with(Consumable {})
+		.with(Stackable::new(1))
 		.with(Ranged { range: 6 })
 		.with(InflictsDamage { damage: 8 })
 		.build();
@@ -46,13 +54,17 @@ pub fn fireball_scroll(ecs: &mut World, x: i32, y: i32) {
 		.with(Position::new(x, y))
 		.with(Renderable {
 			glyph: rltk::to_cp437(')'),
-			fg: RGB::named(rltk::ORANGE),
-			bg: RGB::named(rltk::BLACK),
+			fg: Color::Fire,
+			bg: Color::Background,
 			render_order: render_order::ITEM,
 		})
 		.with(Name::new("Fireball Scroll".to_string()))
+		.with(Description::new("The parchment is warm to the touch. Reading it releases a ball of fire that engulfs everything near the target."))
 		.with(Item {})
+		.with(Weight { weight: 0.5 })
+		.with(Value { gold: 60 })
 		.with(Consumable {})
+		.with(Stackable::new(1))
 		.with(Ranged { range: 6 })
 		.with(InflictsDamage{ damage: 20 })
 		.with(AreaOfEffect { radius: 3 })
@@ -65,18 +77,210 @@ pub fn confusion_scroll(ecs: &mut World, x: i32, y: i32) {
 		.with(Position::new(x, y))
 		.with(Renderable {
 			glyph: rltk::to_cp437(')'),
-			fg: RGB::named(rltk::PINK),
-			bg: RGB::named(rltk::BLACK),
+			fg: Color::Confusion,
+			bg: Color::Background,
 			render_order: render_order::ITEM,
 		})
 		.with(Name::new("Confusion Scroll".to_string()))
+		.with(Description::new("The runes on this scroll seem to squirm. Whoever it is read at forgets which way is up for a while."))
 		.with(Item {})
+		.with(Weight { weight: 0.5 })
+		.with(Value { gold: 40 })
 		.with(Consumable {})
+		.with(Stackable::new(1))
 		.with(Ranged { range: 6 })
 		.with(Confusion { turns: 4 })
 		.build();
 }
 
+pub fn magic_missile_wand(ecs: &mut World, x: i32, y: i32) {
+	ecs.create_entity()
+		.marked::<SimpleMarker<SerializeMe>>()
+		.with(Position::new(x, y))
+		.with(Renderable {
+			glyph: rltk::to_cp437('/'),
+			fg: Color::Magic,
+			bg: Color::Background,
+			render_order: render_order::ITEM,
+		})
+		.with(Name::new("Wand of Magic Missile".to_string()))
+		.with(Description::new("A slim rod of polished ash that fires bolts of force until its charges run dry."))
+		.with(Item {})
+		.with(Weight { weight: 1.0 })
+		.with(Value { gold: 90 })
+		.with(Charges::new(3, true))
+		.with(Ranged { range: 6 })
+		.with(InflictsDamage { damage: 8 })
+		.build();
+}
+
+pub fn confusion_wand(ecs: &mut World, x: i32, y: i32) {
+	ecs.create_entity()
+		.marked::<SimpleMarker<SerializeMe>>()
+		.with(Position::new(x, y))
+		.with(Renderable {
+			glyph: rltk::to_cp437('/'),
+			fg: Color::Confusion,
+			bg: Color::Background,
+			render_order: render_order::ITEM,
+		})
+		.with(Name::new("Wand of Confusion".to_string()))
+		.with(Description::new("A crooked twig that befuddles whoever it is pointed at."))
+		.with(Item {})
+		.with(Weight { weight: 1.0 })
+		.with(Value { gold: 110 })
+		.with(Charges::new(3, true))
+		.with(Ranged { range: 6 })
+		.with(Confusion { turns: 4 })
+		.build();
+}
+
+pub fn fireball_staff(ecs: &mut World, x: i32, y: i32) {
+	ecs.create_entity()
+		.marked::<SimpleMarker<SerializeMe>>()
+		.with(Position::new(x, y))
+		.with(Renderable {
+			glyph: rltk::to_cp437('|'),
+			fg: Color::Fire,
+			bg: Color::Background,
+			render_order: render_order::ITEM,
+		})
+		.with(Name::new("Staff of Fireball".to_string()))
+		.with(Description::new("A heavy staff capped with a smouldering ember. Its charges are spent in bursts of flame."))
+		.with(Item {})
+		.with(Weight { weight: 4.0 })
+		.with(Value { gold: 200 })
+		.with(Charges::new(2, false))
+		.with(Ranged { range: 6 })
+		.with(InflictsDamage{ damage: 20 })
+		.with(AreaOfEffect { radius: 3 })
+		.build();
+}
+
+pub fn recharge_scroll(ecs: &mut World, x: i32, y: i32) {
+	ecs.create_entity()
+		.marked::<SimpleMarker<SerializeMe>>()
+		.with(Position::new(x, y))
+		.with(Renderable {
+			glyph: rltk::to_cp437(')'),
+			fg: Color::Magic,
+			bg: Color::Background,
+			render_order: render_order::ITEM,
+		})
+		.with(Name::new("Scroll of Recharging".to_string()))
+		.with(Description::new("Reading this scroll restores the charges of every wand and staff you carry."))
+		.with(Item {})
+		.with(Weight { weight: 0.5 })
+		.with(Value { gold: 80 })
+		.with(Consumable {})
+		.with(Stackable::new(1))
+		.with(Recharge {})
+		.build();
+}
+
+pub fn dagger(ecs: &mut World, x: i32, y: i32) {
+	ecs.create_entity()
+		.marked::<SimpleMarker<SerializeMe>>()
+		.with(Position::new(x, y))
+		.with(Renderable {
+			glyph: rltk::to_cp437('/'),
+			fg: Color::Weapon,
+			bg: Color::Background,
+			render_order: render_order::ITEM,
+		})
+		.with(Name::new("Dagger".to_string()))
+		.with(Description::new("A short, well-balanced blade. Better than bare hands."))
+		.with(Item {})
+		.with(Weight { weight: 1.0 })
+		.with(Value { gold: 30 })
+		.with(Equippable { slot: EquipmentSlot::Melee })
+		.with(MeleePowerBonus { power: 2 })
+		.build();
+}
+
+pub fn longsword(ecs: &mut World, x: i32, y: i32) {
+	ecs.create_entity()
+		.marked::<SimpleMarker<SerializeMe>>()
+		.with(Position::new(x, y))
+		.with(Renderable {
+			glyph: rltk::to_cp437('/'),
+			fg: Color::Weapon,
+			bg: Color::Background,
+			render_order: render_order::ITEM,
+		})
+		.with(Name::new("Longsword".to_string()))
+		.with(Description::new("A fine steel blade, long enough to keep most foes at bay."))
+		.with(Item {})
+		.with(Weight { weight: 3.0 })
+		.with(Value { gold: 120 })
+		.with(Equippable { slot: EquipmentSlot::Melee })
+		.with(MeleePowerBonus { power: 4 })
+		.build();
+}
+
+pub fn shield(ecs: &mut World, x: i32, y: i32) {
+	ecs.create_entity()
+		.marked::<SimpleMarker<SerializeMe>>()
+		.with(Position::new(x, y))
+		.with(Renderable {
+			glyph: rltk::to_cp437('('),
+			fg: Color::Armor,
+			bg: Color::Background,
+			render_order: render_order::ITEM,
+		})
+		.with(Name::new("Shield".to_string()))
+		.with(Description::new("A battered wooden shield banded with iron."))
+		.with(Item {})
+		.with(Weight { weight: 6.0 })
+		.with(Value { gold: 60 })
+		.with(Equippable { slot: EquipmentSlot::Shield })
+		.with(DefenseBonus { defense: 1 })
+		.build();
+}
+
+pub fn amulet(ecs: &mut World, x: i32, y: i32) {
+	ecs.create_entity()
+		.marked::<SimpleMarker<SerializeMe>>()
+		.with(Position::new(x, y))
+		.with(Renderable {
+			glyph: rltk::to_cp437('"'),
+			fg: Color::QuestItem,
+			bg: Color::Background,
+			render_order: render_order::ITEM,
+		})
+		.with(Name::new("Amulet of the Ancients".to_string()))
+		.with(Description::new("The amulet you came for. Raise it and the dungeon will let you go."))
+		.with(Item {})
+		.with(Weight { weight: 0.5 })
+		.with(QuestItem {})
+		.build();
+}
+
+pub fn gold_pile(ecs: &mut World, x: i32, y: i32) {
+	let amount = {
+		let depth = ecs.fetch::<Map>().depth;
+		ecs.write_resource::<RandomNumberGenerator>().roll_dice(depth + 1, 6)
+	};
+	gold(ecs, x, y, amount);
+}
+
+pub fn gold(ecs: &mut World, x: i32, y: i32, amount: i32) {
+	ecs.create_entity()
+		.marked::<SimpleMarker<SerializeMe>>()
+		.with(Position::new(x, y))
+		.with(Renderable {
+			glyph: rltk::to_cp437('$'),
+			fg: Color::Gold,
+			bg: Color::Background,
+			render_order: render_order::ITEM,
+		})
+		.with(Name::new("Gold".to_string()))
+		.with(Description::new("Coins of a dozen forgotten kingdoms. Shopkeepers are not picky."))
+		.with(Item {})
+		.with(Gold { amount })
+		.build();
+}
+
 pub fn random_item(ecs: &mut World, x: i32, y: i32) {
 	let roll = { 
 		ecs.write_resource::<RandomNumberGenerator>().roll_dice(1, 4)
@@ -88,3 +292,34 @@ pub fn random_item(ecs: &mut World, x: i32, y: i32) {
 		_ => health_potion(ecs, x, y),
 	}
 }
+
+macro_rules! clone_components {
+	($ecs:expr, $from:expr, $to:expr, $( $type:ty),*) => {
+		$(
+			let component = $ecs.read_storage::<$type>().get($from).cloned();
+			if let Some(component) = component {
+				$ecs.write_storage::<$type>().insert($to, component).expect("Failed to clone component");
+			}
+		)*
+	};
+}
+
+/// Takes `amount` items off a stack into a new entity, leaving the rest behind.
+pub fn split_stack(ecs: &mut World, item: Entity, amount: i32) -> Entity {
+	let split = ecs.create_entity()
+		.marked::<SimpleMarker<SerializeMe>>()
+		.build();
+	clone_components!(ecs, item, split,
+		Position, Renderable, Name, Description, Item, InBackpack, ProvidesHealing, Consumable, Ranged,
+		InflictsDamage, AreaOfEffect, Confusion, Charges, Recharge, Stackable, Weight, Value,
+		Equippable, MeleePowerBonus, DefenseBonus, QuestItem);
+
+	let mut stacks = ecs.write_storage::<Stackable>();
+	if let Some(stack) = stacks.get_mut(item) {
+		stack.quantity -= amount;
+	}
+	if let Some(stack) = stacks.get_mut(split) {
+		stack.quantity = amount;
+	}
+	split
+}
@@ -2,7 +2,44 @@ pub mod item;
 mod monster;
 mod player;
 pub mod render_order;
+mod vendor;
 
 pub use item::*;
 pub use monster::*;
 pub use player::*;
+pub use vendor::*;
+
+use specs::World;
+
+use crate::resource::random_table::Spawner;
+
+/// Every template that can be spawned by name from the wizard console.
+pub const TEMPLATES: &[(&str, Spawner)] = &[
+    ("rat", rat),
+    ("kobold", kobold),
+    ("goblin", goblin),
+    ("orc", orc),
+    ("skeleton", skeleton),
+    ("spider", spider),
+    ("troll", troll),
+    ("ogre", ogre),
+    ("dragon", dragon),
+    ("shopkeeper", spawn_shopkeeper),
+    ("health potion", health_potion),
+    ("magic missile scroll", magic_missile_scroll),
+    ("fireball scroll", fireball_scroll),
+    ("confusion scroll", confusion_scroll),
+    ("recharge scroll", recharge_scroll),
+    ("magic missile wand", magic_missile_wand),
+    ("confusion wand", confusion_wand),
+    ("fireball staff", fireball_staff),
+    ("dagger", dagger),
+    ("longsword", longsword),
+    ("shield", shield),
+    ("amulet", amulet),
+    ("gold", gold_pile),
+];
+
+fn spawn_shopkeeper(ecs: &mut World, x: i32, y: i32) {
+    shopkeeper(ecs, x, y);
+}
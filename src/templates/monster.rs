@@ -1,13 +1,15 @@
-use rltk::{RandomNumberGenerator, RGB};
+use rltk::RandomNumberGenerator;
 use specs::{
     saveload::{MarkedBuilder, SimpleMarker},
-    Builder, World, WorldExt,
+    Builder, EntityBuilder, World, WorldExt,
 };
 
-use crate::component::*;
+use crate::{component::*, resource::{map::Map, palette::Color}};
 
 use super::render_order;
 
+pub const CORPSE_DECAY_TURNS: i32 = 100;
+
 pub fn create_monster(world: &mut World, x: i32, y: i32) {
     let roll = {
         world
@@ -20,28 +22,244 @@ pub fn create_monster(world: &mut World, x: i32, y: i32) {
     }
 }
 
+/// Base stat block of a monster species, before depth scaling.
+pub struct Species {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub glyph: char,
+    pub color: Color,
+    pub max_hp: i32,
+    pub defense: i32,
+    pub power: i32,
+    /// Loot table rolled on death, if the species drops anything.
+    pub loot: Option<Loot>,
+    pub corpse: bool,
+}
+
+pub const RAT: Species = Species {
+    name: "Rat",
+    description: "A mangy rat the size of a small dog. Alone it is a nuisance.",
+    glyph: 'r',
+    color: Color::HostileWeak,
+    max_hp: 6,
+    defense: 0,
+    power: 2,
+    loot: None,
+    corpse: true,
+};
+
+pub const KOBOLD: Species = Species {
+    name: "Kobold",
+    description: "A scaly little schemer with a rusty knife and a grudge.",
+    glyph: 'k',
+    color: Color::HostileWeak,
+    max_hp: 10,
+    defense: 0,
+    power: 3,
+    loot: Some(Loot::Kobold),
+    corpse: true,
+};
+
+pub const GOBLIN: Species = Species {
+    name: "Goblin",
+    description: "A wiry green raider, quick to strike and quicker to flee.",
+    glyph: 'g',
+    color: Color::Hostile,
+    max_hp: 12,
+    defense: 1,
+    power: 3,
+    loot: Some(Loot::Goblin),
+    corpse: true,
+};
+
+pub const ORC: Species = Species {
+    name: "Orc",
+    description: "A brutish warrior in scavenged armour, eager for a fight.",
+    glyph: 'o',
+    color: Color::Hostile,
+    max_hp: 18,
+    defense: 2,
+    power: 5,
+    loot: Some(Loot::Orc),
+    corpse: true,
+};
+
+pub const SKELETON: Species = Species {
+    name: "Skeleton",
+    description: "Old bones held together by older magic. It feels no pain.",
+    glyph: 's',
+    color: Color::HostileUndead,
+    max_hp: 20,
+    defense: 3,
+    power: 5,
+    loot: Some(Loot::Skeleton),
+    corpse: false,
+};
+
+pub const SPIDER: Species = Species {
+    name: "Giant Spider",
+    description: "A hairy spider as big as a pony. Its bite carries a nasty sting.",
+    glyph: 'S',
+    color: Color::HostileVenomous,
+    max_hp: 16,
+    defense: 1,
+    power: 7,
+    loot: Some(Loot::Spider),
+    corpse: true,
+};
+
+pub const TROLL: Species = Species {
+    name: "Troll",
+    description: "A hulking brute with warty green hide and a bottomless appetite.",
+    glyph: 'T',
+    color: Color::HostileBrute,
+    max_hp: 26,
+    defense: 2,
+    power: 5,
+    loot: Some(Loot::Troll),
+    corpse: true,
+};
+
+pub const OGRE: Species = Species {
+    name: "Ogre",
+    description: "A towering lump of muscle that swings a tree trunk like a club.",
+    glyph: 'O',
+    color: Color::HostileBrute,
+    max_hp: 24,
+    defense: 0,
+    power: 7,
+    loot: Some(Loot::Ogre),
+    corpse: true,
+};
+
+/// The boss is not scaled with depth. Against a player in the best gear (power 9,
+/// defense 3, 30 hp) trading blows takes about nine turns and costs around 36 hp; see
+/// `spawn_boss_arena` for the fight as a whole.
+pub const DRAGON: Species = Species {
+    name: "Ancient Dragon",
+    description: "An ancient wyrm coiled around the Amulet. Its scales knit back together and its breath is fire.",
+    glyph: 'D',
+    color: Color::Boss,
+    max_hp: 50,
+    defense: 2,
+    power: 7,
+    loot: Some(Loot::Boss),
+    corpse: true,
+};
+
+pub fn rat(ecs: &mut World, x: i32, y: i32) {
+    monster(ecs, x, y, &RAT);
+}
+
+pub fn kobold(ecs: &mut World, x: i32, y: i32) {
+    monster(ecs, x, y, &KOBOLD);
+}
+
 pub fn goblin(ecs: &mut World, x: i32, y: i32) {
-    monster(ecs, x, y, rltk::to_cp437('g'), "Goblin");
+    monster(ecs, x, y, &GOBLIN);
 }
 
 pub fn orc(ecs: &mut World, x: i32, y: i32) {
-    monster(ecs, x, y, rltk::to_cp437('o'), "Orc");
+    monster(ecs, x, y, &ORC);
 }
 
-fn monster<S: ToString>(ecs: &mut World, x: i32, y: i32, glyph: rltk::FontCharType, name: S) {
-    ecs.create_entity()
+pub fn skeleton(ecs: &mut World, x: i32, y: i32) {
+    monster(ecs, x, y, &SKELETON);
+}
+
+pub fn spider(ecs: &mut World, x: i32, y: i32) {
+    monster(ecs, x, y, &SPIDER);
+}
+
+pub fn troll(ecs: &mut World, x: i32, y: i32) {
+    monster(ecs, x, y, &TROLL);
+}
+
+pub fn ogre(ecs: &mut World, x: i32, y: i32) {
+    monster(ecs, x, y, &OGRE);
+}
+
+pub fn dragon(ecs: &mut World, x: i32, y: i32) {
+    let stats = CombatStats::new(DRAGON.max_hp, DRAGON.defense, DRAGON.power);
+    monster_builder(ecs, x, y, &DRAGON, stats)
+        .with(Viewshed::new(12))
+        .with(LootTable::new(Loot::Boss, 100))
+        .with(Boss {
+            breath_damage: 12,
+            breath_range: 7,
+            cooldown: 0,
+        })
+        .build();
+}
+
+/// The dragon's guards are not scaled with depth either. They fall to a single blow from
+/// a longsword and barely scratch a shielded player.
+pub fn skeleton_guard(ecs: &mut World, x: i32, y: i32) {
+    monster_builder(ecs, x, y, &SKELETON, CombatStats::new(8, 1, 4)).build();
+}
+
+/// Monsters grow a sixteenth of their base health every level below the first,
+/// and gain a point of power every fifth level and of defense every sixth. The player
+/// never levels up, so the curve stays shallow: the worst regular fight, a depth 8 ogre
+/// at 34 hp, 1 defense and 8 power, costs a player with the longsword and shield about
+/// 20 hp.
+pub fn scaled_stats(species: &Species, depth: i32) -> CombatStats {
+    let levels = i32::max(0, depth - 1);
+    CombatStats::new(
+        species.max_hp + species.max_hp * levels / 16,
+        species.defense + levels / 6,
+        species.power + levels / 5,
+    )
+}
+
+fn monster(ecs: &mut World, x: i32, y: i32, species: &Species) {
+    let depth = ecs.fetch::<Map>().depth;
+    monster_builder(ecs, x, y, species, scaled_stats(species, depth)).build();
+}
+
+fn monster_builder<'a>(ecs: &'a mut World, x: i32, y: i32, species: &Species, stats: CombatStats) -> EntityBuilder<'a> {
+    let gold = {
+        ecs.write_resource::<RandomNumberGenerator>()
+            .roll_dice(1, 8)
+            - 4
+    };
+    let mut builder = ecs.create_entity()
         .marked::<SimpleMarker<SerializeMe>>()
         .with(Position::new(x, y))
         .with(Renderable {
-            glyph,
-            fg: RGB::named(rltk::RED),
-            bg: RGB::named(rltk::BLACK),
+            glyph: rltk::to_cp437(species.glyph),
+            fg: species.color,
+            bg: Color::Background,
             render_order: render_order::MONSTER,
         })
         .with(Viewshed::new(8))
         .with(Monster::new())
-        .with(Name::new(name.to_string()))
+        .with(Name::new(species.name))
+        .with(Description::new(species.description))
         .with(BlocksTile {})
-        .with(CombatStats::new(16, 1, 4))
+        .with(stats)
+        .with(Purse::new(i32::max(0, gold)));
+    if let Some(loot) = species.loot {
+        builder = builder.with(LootTable::new(loot, 30));
+    }
+    if species.corpse {
+        builder = builder.with(LeavesCorpse {});
+    }
+    builder
+}
+
+pub fn corpse<S: ToString>(ecs: &mut World, x: i32, y: i32, name: S) {
+    ecs.create_entity()
+        .marked::<SimpleMarker<SerializeMe>>()
+        .with(Position::new(x, y))
+        .with(Renderable {
+            glyph: rltk::to_cp437('%'),
+            fg: Color::Corpse,
+            bg: Color::Background,
+            render_order: render_order::CORPSE,
+        })
+        .with(Name::new(format!("{} corpse", name.to_string())))
+        .with(Description::new("What is left of something that lost a fight. It will not last long."))
+        .with(Decays { turns: CORPSE_DECAY_TURNS })
         .build();
 }
@@ -1,7 +1,6 @@
-use rltk::RGB;
 use specs::{saveload::{MarkedBuilder, SimpleMarker}, Builder, Entity, World, WorldExt};
 
-use crate::component::*;
+use crate::{component::*, resource::palette::Color};
 
 use super::render_order;
 
@@ -14,13 +13,15 @@ pub fn create_player(
 		.with(Position::new(x, y))
 		.with(Renderable::new(
 			rltk::to_cp437('@'),
-			RGB::named(rltk::YELLOW),
-			RGB::named(rltk::BLACK),
+			Color::Player,
+			Color::Background,
 			render_order::PLAYER
 		))
 		.with(Viewshed::new(8))
 		.with(Player{})
 		.with(Name::new("Player".to_string()))
+		.with(Description::new("That's you: an adventurer come to claim the Amulet of the Ancients."))
 		.with(CombatStats::new(30, 2, 5))
+		.with(Purse::new(0))
 		.build()
 }
@@ -1,3 +1,4 @@
 pub const PLAYER: i32 = 0;
 pub const MONSTER: i32 = 1;
 pub const ITEM: i32 = 2;
+pub const CORPSE: i32 = 3;
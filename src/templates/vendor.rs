@@ -0,0 +1,26 @@
+use specs::{
+    saveload::{MarkedBuilder, SimpleMarker},
+    Builder, Entity, World, WorldExt,
+};
+
+use crate::{component::*, resource::palette::Color};
+
+use super::render_order;
+
+pub fn shopkeeper(ecs: &mut World, x: i32, y: i32) -> Entity {
+    ecs.create_entity()
+        .marked::<SimpleMarker<SerializeMe>>()
+        .with(Position::new(x, y))
+        .with(Renderable {
+            glyph: rltk::to_cp437('@'),
+            fg: Color::Vendor,
+            bg: Color::Background,
+            render_order: render_order::MONSTER,
+        })
+        .with(Name::new("Shopkeeper"))
+        .with(Description::new("A wiry merchant who somehow makes a living this far underground. Walk into them to trade."))
+        .with(BlocksTile {})
+        .with(Vendor {})
+        .with(Purse::new(500))
+        .build()
+}